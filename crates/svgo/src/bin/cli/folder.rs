@@ -0,0 +1,172 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use anyhow::{Context, Result};
+
+/// Pattern used to select files in a folder, `*` matches any characters but
+/// `/`, `?` matches a single character and `**` matches any number of
+/// directories.
+///
+/// Patterns without `/` match file names, other patterns match paths relative
+/// to the folder.
+#[derive(Clone, Debug)]
+pub struct Glob {
+    segments: Vec<Vec<char>>,
+}
+
+impl FromStr for Glob {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() {
+            return Err("Empty glob pattern".to_string());
+        }
+
+        Ok(Self {
+            segments: s
+                .split('/')
+                .filter(|segment| !segment.is_empty())
+                .map(|segment| segment.chars().collect())
+                .collect(),
+        })
+    }
+}
+
+impl Glob {
+    pub fn matches(&self, relative: &Path) -> bool {
+        let names: Vec<Vec<char>> = relative
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy().chars().collect())
+            .collect();
+
+        match self.segments.as_slice() {
+            [name] if name.as_slice() != ['*', '*'] => names
+                .last()
+                .is_some_and(|last| Self::matches_name(name, last)),
+            segments => Self::matches_segments(segments, &names),
+        }
+    }
+
+    fn matches_segments(segments: &[Vec<char>], names: &[Vec<char>]) -> bool {
+        match segments.split_first() {
+            None => names.is_empty(),
+            Some((segment, rest)) if segment.as_slice() == ['*', '*'] => {
+                (0..=names.len()).any(|skip| Self::matches_segments(rest, &names[skip..]))
+            }
+            Some((segment, rest)) => names.split_first().is_some_and(|(name, names)| {
+                Self::matches_name(segment, name) && Self::matches_segments(rest, names)
+            }),
+        }
+    }
+
+    fn matches_name(pattern: &[char], name: &[char]) -> bool {
+        match pattern.split_first() {
+            None => name.is_empty(),
+            Some(('*', rest)) => {
+                (0..=name.len()).any(|skip| Self::matches_name(rest, &name[skip..]))
+            }
+            Some(('?', rest)) => !name.is_empty() && Self::matches_name(rest, &name[1..]),
+            Some((ch, rest)) => name.first() == Some(ch) && Self::matches_name(rest, &name[1..]),
+        }
+    }
+}
+
+/// Returns the files in `folder` and its subfolders matching any of the
+/// `include` patterns but none of the `exclude` ones, along with their path
+/// relative to `folder`.
+pub fn walk(folder: &Path, include: &[Glob], exclude: &[Glob]) -> Result<Vec<(PathBuf, PathBuf)>> {
+    let mut files = Vec::new();
+    let mut pending = vec![folder.to_path_buf()];
+
+    while let Some(dir) = pending.pop() {
+        let mut entries = fs::read_dir(&dir)
+            .with_context(|| format!("Failed to read {}", dir.display()))?
+            .collect::<std::io::Result<Vec<_>>>()
+            .with_context(|| format!("Failed to read {}", dir.display()))?;
+        entries.sort_by_key(|entry| entry.file_name());
+
+        // Subfolders are pushed in reverse so they are visited in order
+        for entry in entries.iter().rev() {
+            let path = entry.path();
+
+            if entry.file_type()?.is_dir() {
+                pending.push(path);
+            }
+        }
+
+        for entry in entries {
+            let path = entry.path();
+
+            if entry.file_type()?.is_dir() || !path.is_file() {
+                continue;
+            }
+
+            let relative = path.strip_prefix(folder)?.to_path_buf();
+
+            if include.iter().any(|glob| glob.matches(&relative))
+                && !exclude.iter().any(|glob| glob.matches(&relative))
+            {
+                files.push((path, relative));
+            }
+        }
+    }
+
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matches(pattern: &str, path: &str) -> bool {
+        pattern.parse::<Glob>().unwrap().matches(Path::new(path))
+    }
+
+    #[test]
+    fn matches_glob_patterns() {
+        assert!(matches("*.svg", "icons/arrows/left.svg"));
+        assert!(!matches("*.svg", "icons/left.png"));
+        assert!(matches("icons/*/le?t.svg", "icons/arrows/left.svg"));
+        assert!(!matches("icons/*.svg", "icons/arrows/left.svg"));
+        assert!(matches("icons/**/*.svg", "icons/left.svg"));
+        assert!(matches("**/legacy/**", "icons/legacy/a/b.svg"));
+        assert!(!matches("**/legacy/**", "icons/legacy.svg"));
+    }
+
+    #[test]
+    fn walks_folders_recursively() {
+        let folder = std::env::temp_dir().join(format!("svgo-walk-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&folder);
+
+        for file in [
+            "a.svg",
+            "notes.txt",
+            "icons/b.svg",
+            "icons/nested/c.svg",
+            "icons/nested/d.png",
+            "legacy/e.svg",
+        ] {
+            let path = folder.join(file);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, "<svg/>").unwrap();
+        }
+
+        let include = ["*.svg".parse::<Glob>().unwrap()];
+        let exclude = ["legacy/**".parse::<Glob>().unwrap()];
+        let files = walk(&folder, &include, &exclude);
+        fs::remove_dir_all(&folder).unwrap();
+
+        let expected = ["a.svg", "icons/b.svg", "icons/nested/c.svg"];
+
+        assert_eq!(
+            files.unwrap(),
+            expected
+                .iter()
+                .map(|relative| (folder.join(relative), PathBuf::from(relative)))
+                .collect::<Vec<_>>()
+        );
+    }
+}
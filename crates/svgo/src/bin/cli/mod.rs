@@ -1,11 +1,45 @@
-use std::{fs::File, io::stdout, path::PathBuf};
+use std::{
+    collections::HashMap,
+    fs::{self, File},
+    io::{stdin, stdout, IsTerminal},
+    path::{Path, PathBuf},
+    str::FromStr,
+};
 
-use anyhow::Result;
-use clap::Parser;
+mod folder;
 
-use svgo::optimizer::optimization::RemoveCommentsOptimization;
-use svgo::optimizer::optimization::RemoveDoctypeOptimization;
-use svgo::optimizer::Optimization;
+use anyhow::{Context, Result};
+use clap::{ArgAction, Parser};
+use tracing::level_filters::LevelFilter;
+
+use svgo::optimizer::{Optimization, Optimizer, Plugin, DEFAULT_MAX_PASSES};
+use svgo::svg::parser::Parser as SvgParser;
+use svgo::svg::writer::{Eol, Layout, WriterOptions};
+use svgo::svg::Svg;
+
+use self::folder::Glob;
+
+/// Path standing for stdin in the list of SVGs
+const STDIN: &str = "-";
+
+/// Number of decimal places kept when rounding numbers, `None` keeps every
+/// decimal place.
+#[derive(Clone, Copy, Debug)]
+pub struct Precision(Option<u8>);
+
+impl FromStr for Precision {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s == "lossless" {
+            return Ok(Self(None));
+        }
+
+        s.parse()
+            .map(|precision| Self(Some(precision)))
+            .map_err(|_| format!("Invalid precision \"{}\"", s))
+    }
+}
 
 #[derive(Debug, Parser)]
 #[command(
@@ -15,38 +49,347 @@ use svgo::optimizer::Optimization;
     next_line_help = true
 )]
 pub struct SvgoCli {
-    /// Space separated list of SVGs to optimize
+    /// Space separated list of SVGs to optimize, `-` reads a SVG from stdin
+    /// which is also read when no SVG is provided and stdin is piped
     pub files: Vec<PathBuf>,
-    /// Removes Comments from SVG
+    /// Folders to optimize the SVGs of, along with the ones in their
+    /// subfolders
+    #[clap(short = 'r', long)]
+    pub folder: Vec<PathBuf>,
+    /// Glob patterns selecting the files optimized in folders, defaults to
+    /// `*.svg`
+    #[clap(long, requires = "folder")]
+    pub include: Vec<Glob>,
+    /// Glob patterns of files skipped in folders
+    #[clap(long, requires = "folder")]
+    pub exclude: Vec<Glob>,
+    /// File the optimized SVG is written to instead of stdout, only
+    /// available with a single SVG
+    #[clap(short, long, conflicts_with_all = ["output_dir", "in_place"])]
+    pub output: Option<PathBuf>,
+    /// Directory optimized SVGs are written to, keeping their file names,
+    /// SVGs in folders keep their path relative to the folder
+    #[clap(long, conflicts_with = "in_place")]
+    pub output_dir: Option<PathBuf>,
+    /// Overwrites each SVG with its optimized version
     #[clap(long)]
-    pub remove_comments: bool,
-    /// Removes DOCTYPE from SVG
+    pub in_place: bool,
+    /// Comma separated list of optimizations to perform along with the
+    /// ones performed by default
+    #[clap(long, value_delimiter = ',')]
+    pub enable: Vec<String>,
+    /// Comma separated list of optimizations to skip, takes precedence over
+    /// `--enable`
+    #[clap(long, value_delimiter = ',')]
+    pub disable: Vec<String>,
+    /// Lists every available optimization along with whether it is
+    /// performed by default
     #[clap(long)]
-    pub remove_doctype: bool,
+    pub show_plugins: bool,
+    /// Number of decimal places kept in numeric values, path data and
+    /// transforms, or `lossless` to keep every decimal place
+    #[clap(long)]
+    pub precision: Option<Precision>,
+    /// Repeats optimizations until the output stops shrinking
+    #[clap(long)]
+    pub multipass: bool,
+    /// Maximum number of passes performed when `--multipass` is set
+    #[clap(long, default_value_t = DEFAULT_MAX_PASSES, requires = "multipass")]
+    pub max_passes: usize,
+    /// Keeps what can be read from malformed SVGs instead of failing,
+    /// reporting parse errors as warnings
+    #[clap(long)]
+    pub recover: bool,
+    /// Writes the parts of SVGs left untouched by optimizations exactly as
+    /// they were read
+    #[clap(long)]
+    pub lossless: bool,
+    /// Writes every element on its own line, indented by its depth
+    #[clap(long, conflicts_with = "minify")]
+    pub pretty: bool,
+    /// Number of spaces each depth level is indented by when `--pretty` is
+    /// set
+    #[clap(long, default_value_t = 4, requires = "pretty")]
+    pub indent: usize,
+    /// Line ending of the lines broken by the writer, either `lf` or `crlf`
+    #[clap(long, default_value = "lf")]
+    pub eol: Eol,
+    /// Drops whitespace between tags where it is not significant
+    #[clap(long)]
+    pub minify: bool,
+    /// Writes elements without children with an opening and a closing tag
+    /// instead of a self-closing tag
+    #[clap(long)]
+    pub no_self_closing: bool,
+    /// Logs more details, repeat to increase verbosity. Ignored when
+    /// `RUST_LOG` is set
+    #[clap(short, long, action = ArgAction::Count, conflicts_with = "quiet")]
+    pub verbose: u8,
+    /// Logs errors only, repeat to disable logs. Ignored when `RUST_LOG` is
+    /// set
+    #[clap(short, long, action = ArgAction::Count)]
+    pub quiet: u8,
 }
 
 impl SvgoCli {
+    /// Returns the most verbose level logged, warnings by default
+    pub fn log_level(&self) -> LevelFilter {
+        match (self.verbose, self.quiet) {
+            (0, 0) => LevelFilter::WARN,
+            (0, 1) => LevelFilter::ERROR,
+            (0, _) => LevelFilter::OFF,
+            (1, _) => LevelFilter::INFO,
+            (2, _) => LevelFilter::DEBUG,
+            _ => LevelFilter::TRACE,
+        }
+    }
+
+    fn writer_options(&self) -> WriterOptions {
+        let layout = if self.pretty {
+            Layout::Pretty
+        } else if self.minify {
+            Layout::Minify
+        } else {
+            Layout::Preserve
+        };
+
+        WriterOptions::new()
+            .layout(layout)
+            .indent(self.indent, ' ')
+            .eol(self.eol)
+            .final_newline(self.pretty)
+            .self_closing(!self.no_self_closing)
+    }
+
+    /// Returns the SVGs to optimize along with the path they are written to
+    /// relative to the output directory.
+    fn inputs(&self) -> Result<Vec<(PathBuf, PathBuf)>> {
+        let mut inputs: Vec<(PathBuf, PathBuf)> = self
+            .files
+            .iter()
+            .map(|file| {
+                let name = file.file_name().unwrap_or(file.as_os_str());
+                (file.clone(), PathBuf::from(name))
+            })
+            .collect();
+
+        if self.files.is_empty() && self.folder.is_empty() && !stdin().is_terminal() {
+            inputs.push((PathBuf::from(STDIN), PathBuf::from(STDIN)));
+        }
+
+        let include = match self.include.is_empty() {
+            true => vec![Glob::from_str("*.svg").map_err(anyhow::Error::msg)?],
+            false => self.include.clone(),
+        };
+
+        for folder in &self.folder {
+            inputs.extend(folder::walk(folder, &include, &self.exclude)?);
+        }
+
+        Ok(inputs)
+    }
+
+    /// Returns the path the optimized version of `file` is written to, or
+    /// `None` to write it to stdout.
+    fn destination(&self, file: &Path, relative: &Path) -> Option<PathBuf> {
+        if let Some(output) = &self.output {
+            return Some(output.clone());
+        }
+
+        // SVGs from stdin have no file to mirror or overwrite
+        if file == Path::new(STDIN) {
+            return None;
+        }
+
+        if let Some(output_dir) = &self.output_dir {
+            return Some(output_dir.join(relative));
+        }
+
+        self.in_place.then(|| file.to_path_buf())
+    }
+
+    /// Returns the optimizer performing the default optimizations along
+    /// with the enabled ones, except the disabled ones.
+    fn optimizer(&self) -> Optimizer {
+        let mut optimizer = Optimizer::new();
+
+        for mut optimization in Optimization::all() {
+            let name = optimization.name().to_string();
+
+            if self.disable.contains(&name)
+                || !(optimization.is_default() || self.enable.contains(&name))
+            {
+                continue;
+            }
+
+            if let Some(Precision(precision)) = self.precision {
+                optimization.set_precision(precision);
+            }
+
+            optimizer.append(optimization);
+        }
+
+        if self.multipass {
+            optimizer.set_multipass(Some(self.max_passes));
+        }
+
+        optimizer
+    }
+
+    /// Returns a line for every available optimization with its name,
+    /// whether it is performed by default and its description.
+    fn plugin_list() -> Vec<String> {
+        Optimization::all()
+            .iter()
+            .map(|optimization| {
+                let state = match optimization.is_default() {
+                    true => "enabled",
+                    false => "disabled",
+                };
+
+                format!(
+                    "{:<24} {:<9} {}",
+                    optimization.name(),
+                    state,
+                    optimization.description()
+                )
+            })
+            .collect()
+    }
+
     pub fn exec(self) -> Result<()> {
-        if self.files.is_empty() {
+        if self.show_plugins {
+            for line in Self::plugin_list() {
+                println!("{}", line);
+            }
+
+            return Ok(());
+        }
+
+        for name in self.enable.iter().chain(&self.disable) {
+            name.parse::<Optimization>()?;
+        }
+
+        let inputs = self.inputs()?;
+
+        if inputs.is_empty() {
             return Err(anyhow::anyhow!("No files to optimize"));
         }
 
-        for file in self.files {
-            let buf = File::open(&file)?;
-            let mut svgo = svgo::SvgOptimizer::open(buf)?;
+        if inputs.len() > 1 && self.output.is_some() {
+            return Err(anyhow::anyhow!(
+                "`--output` takes a single file, use `--output-dir` instead"
+            ));
+        }
+
+        if inputs.len() > 1 && self.output_dir.is_none() && !self.in_place {
+            return Err(anyhow::anyhow!(
+                "Several files can only be written with `--output-dir` or `--in-place`"
+            ));
+        }
+
+        // Checked before writing anything so no optimized file overwrites
+        // another one
+        let mut destinations = HashMap::new();
+
+        for (file, relative) in &inputs {
+            let Some(destination) = self.destination(file, relative) else {
+                continue;
+            };
 
-            if self.remove_comments {
-                svgo.add_optimization(Optimization::RemoveComments(RemoveCommentsOptimization));
+            if let Some(other) = destinations.insert(destination.clone(), file) {
+                return Err(anyhow::anyhow!(
+                    "Both {} and {} would be written to {}",
+                    other.display(),
+                    file.display(),
+                    destination.display()
+                ));
             }
+        }
+
+        let writer_options = self.writer_options();
+
+        for (file, relative) in &inputs {
+            let parser = SvgParser::new()
+                .recover(self.recover)
+                .lossless(self.lossless);
+            let read = match file == Path::new(STDIN) {
+                true => Svg::read_with(stdin().lock(), &parser),
+                false => Svg::read_with(File::open(file)?, &parser),
+            };
+            let (svg, warnings) =
+                read.with_context(|| format!("Failed to parse {}", file.display()))?;
 
-            if self.remove_doctype {
-                svgo.add_optimization(Optimization::RemoveDoctype(RemoveDoctypeOptimization));
+            for warning in warnings {
+                tracing::warn!("{}: {}", file.display(), warning);
             }
 
+            let mut svgo = svgo::SvgOptimizer::new(svg, self.optimizer());
+
             svgo.optimize()?;
-            svgo.write(stdout())?;
+
+            match self.destination(file, relative) {
+                Some(destination) => {
+                    if let Some(parent) = destination.parent() {
+                        fs::create_dir_all(parent)
+                            .with_context(|| format!("Failed to create {}", parent.display()))?;
+                    }
+
+                    let mut output = Vec::new();
+                    svgo.write_with(&mut output, &writer_options)?;
+                    fs::write(&destination, output)
+                        .with_context(|| format!("Failed to write {}", destination.display()))?;
+                }
+                None => svgo.write_with(stdout(), &writer_options)?,
+            }
         }
 
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lists_plugins_with_their_default_state() {
+        let lines: Vec<String> = SvgoCli::plugin_list()
+            .iter()
+            .map(|line| line.trim_end().to_string())
+            .collect();
+
+        assert_eq!(
+            lines,
+            vec![
+                "remove-doctype           disabled  Removes the DOCTYPE declaration from the SVG document",
+                "remove-xml-proc-inst     disabled  Removes processing instructions from the SVG document",
+                "remove-xml-declaration   disabled  Removes the XML declaration from the SVG document",
+                "remove-comments          disabled  Removes comments from the SVG document",
+                "cleanup-numeric-values   disabled  Rounds numeric values and writes them in their shortest form",
+                "convert-colors           disabled  Rewrites colors in their shortest form",
+                "apply-transforms         disabled  Applies transforms into path coordinates",
+                "convert-path-data        disabled  Rewrites path data in its shortest form",
+                "convert-transform        disabled  Collapses transforms into their shortest form",
+            ]
+        );
+    }
+
+    #[test]
+    fn performs_enabled_plugins_except_disabled_ones() {
+        let cli = SvgoCli::parse_from([
+            "svgo",
+            "--enable",
+            "remove-comments,remove-doctype,convert-colors",
+            "--disable",
+            "remove-doctype",
+        ]);
+        let names: Vec<String> = cli
+            .optimizer()
+            .plugins()
+            .map(|plugin| plugin.name().to_string())
+            .collect();
+
+        assert_eq!(names, vec!["remove-comments", "convert-colors"]);
+    }
+}
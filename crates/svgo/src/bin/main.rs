@@ -1,23 +1,39 @@
 mod cli;
 
-use anyhow::Result;
+use std::io::{stderr, IsTerminal};
+
+use anyhow::{Context, Result};
 use clap::Parser;
-use tracing::Level;
-use tracing_subscriber::FmtSubscriber;
+use tracing_subscriber::filter::Targets;
+use tracing_subscriber::layer::SubscriberExt;
 
 use self::cli::SvgoCli;
 
 fn main() -> Result<()> {
-    let subscriber = FmtSubscriber::builder()
-        // all spans/events with a level higher than TRACE (e.g, debug, info, warn, etc.)
-        // will be written to stdout.
-        .with_max_level(Level::TRACE)
-        // completes the builder.
-        .finish();
+    let args = SvgoCli::parse();
 
-    tracing::subscriber::set_global_default(subscriber).expect("setting default subscriber failed");
+    // Directives such as `svgo=debug,warn` in `RUST_LOG` take precedence
+    // over the verbosity flags, an empty `RUST_LOG` counts as unset
+    let directives = std::env::var("RUST_LOG")
+        .ok()
+        .filter(|directives| !directives.trim().is_empty());
+    let filter = match directives {
+        Some(directives) => directives
+            .parse::<Targets>()
+            .context("Invalid RUST_LOG directives")?,
+        None => Targets::new().with_default(args.log_level()),
+    };
 
-    let args = SvgoCli::parse();
+    // Logs are written to stderr so they never mix with SVGs written to stdout
+    let subscriber = tracing_subscriber::registry()
+        .with(
+            tracing_subscriber::fmt::layer()
+                .with_writer(stderr)
+                .with_ansi(stderr().is_terminal()),
+        )
+        .with(filter);
+
+    tracing::subscriber::set_global_default(subscriber).expect("setting default subscriber failed");
 
     args.exec()?;
     Ok(())
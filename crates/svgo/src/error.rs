@@ -0,0 +1,65 @@
+use std::fmt::Display;
+
+use crate::svg::parser::ParseError;
+
+pub type Result<T, E = SvgoError> = std::result::Result<T, E>;
+
+/// Errors returned by the `svgo` library.
+///
+/// The underlying error of a failure, if any, is available through
+/// [`std::error::Error::source`].
+#[derive(Debug)]
+pub enum SvgoError {
+    /// Reading the SVG document failed
+    Io(std::io::Error),
+    /// The SVG document is malformed
+    Parse(ParseError),
+    /// The SVG document holds a construct which is not supported
+    Unsupported(String),
+    /// A plugin failed to apply
+    Plugin {
+        name: String,
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+    /// No plugin with the provided name is available
+    UnknownPlugin(String),
+    /// Writing the SVG document failed
+    Write(Box<dyn std::error::Error + Send + Sync>),
+}
+
+impl Display for SvgoError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(_) => write!(f, "Failed to read SVG document"),
+            Self::Parse(_) => write!(f, "Malformed SVG document"),
+            Self::Unsupported(construct) => write!(f, "Unsupported {}", construct),
+            Self::Plugin { name, .. } => write!(f, "Plugin \"{}\" failed", name),
+            Self::UnknownPlugin(name) => write!(f, "No plugin named \"{}\"", name),
+            Self::Write(_) => write!(f, "Failed to write SVG document"),
+        }
+    }
+}
+
+impl std::error::Error for SvgoError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(err) => Some(err),
+            Self::Parse(err) => Some(err),
+            Self::Plugin { source, .. } => Some(source.as_ref()),
+            Self::Write(err) => Some(err.as_ref()),
+            Self::Unsupported(_) | Self::UnknownPlugin(_) => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for SvgoError {
+    fn from(err: std::io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+impl From<ParseError> for SvgoError {
+    fn from(err: ParseError) -> Self {
+        Self::Parse(err)
+    }
+}
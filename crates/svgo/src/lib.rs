@@ -1,11 +1,13 @@
+pub mod error;
 pub mod optimizer;
 pub mod svg;
 
 use std::fs::File;
 
-use anyhow::Result;
+pub use error::{Result, SvgoError};
 
-use optimizer::{Optimization, Optimizer};
+use optimizer::{Optimization, Optimizer, Plugin};
+use svg::writer::WriterOptions;
 use svg::Svg;
 
 pub struct SvgOptimizer {
@@ -38,14 +40,36 @@ impl SvgOptimizer {
         self.svg.write(write)
     }
 
+    /// Writes the underlying [`Svg`] to a [`std::io::Write`] instance using
+    /// the provided [`WriterOptions`].
+    pub fn write_with<W: std::io::Write>(&self, write: W, options: &WriterOptions) -> Result<()> {
+        self.svg.write_with(write, options)
+    }
+
     /// Performs the optimizations on the SVG.
+    ///
+    /// When multipass is enabled through [`SvgOptimizer::set_multipass`],
+    /// optimizations are performed repeatedly until the output stops
+    /// shrinking.
     pub fn optimize(&mut self) -> Result<()> {
         self.optimizer.apply(&mut self.svg)
     }
 
+    /// Enables multipass optimization capped to `max_passes`, or disables it
+    /// when `None` is provided.
+    pub fn set_multipass(&mut self, max_passes: Option<usize>) {
+        self.optimizer.set_multipass(max_passes);
+    }
+
     /// Appends an [`Optimization`] to be performed on the SVG when
     /// the [`optimize`] method is called.
     pub fn add_optimization(&mut self, optim: Optimization) {
         self.optimizer.append(optim);
     }
+
+    /// Appends a custom [`Plugin`] to be performed on the SVG when the
+    /// [`optimize`] method is called.
+    pub fn add_plugin(&mut self, plugin: Box<dyn Plugin>) {
+        self.optimizer.append_plugin(plugin);
+    }
 }
@@ -1,11 +1,22 @@
 pub mod optimization;
+pub mod plugin;
 
-use std::collections::HashSet;
+use std::str::FromStr;
 
+use crate::error::SvgoError;
 use crate::svg::Svg;
 
+use self::optimization::apply_transforms::ApplyTransformsOptimization;
+use self::optimization::cleanup_numeric_values::CleanupNumericValuesOptimization;
+use self::optimization::convert_colors::ConvertColorsOptimization;
+use self::optimization::convert_path_data::ConvertPathDataOptimization;
+use self::optimization::convert_transform::ConvertTransformOptimization;
 use self::optimization::remove_comments::RemoveCommentsOptimization;
 use self::optimization::remove_doctype::RemoveDoctypeOptimization;
+use self::optimization::remove_xml_declaration::RemoveXmlDeclarationOptimization;
+use self::optimization::remove_xml_proc_inst::RemoveXmlProcInstOptimization;
+
+pub use self::plugin::Plugin;
 
 #[derive(Debug, Hash, Eq, PartialEq)]
 pub enum Optimization {
@@ -13,10 +24,124 @@ pub enum Optimization {
     RemoveComments(RemoveCommentsOptimization),
     /// Remove Doctype declaration from the SVG document.
     RemoveDoctype(RemoveDoctypeOptimization),
+    /// Rewrite path data in its shortest form.
+    ConvertPathData(ConvertPathDataOptimization),
+    /// Round numeric values and write them in their shortest form.
+    CleanupNumericValues(CleanupNumericValuesOptimization),
+    /// Rewrite colors in their shortest form.
+    ConvertColors(ConvertColorsOptimization),
+    /// Collapse transforms into their shortest form.
+    ConvertTransform(ConvertTransformOptimization),
+    /// Apply transforms directly into path coordinates.
+    ApplyTransforms(ApplyTransformsOptimization),
+    /// Remove the XML declaration from the SVG document.
+    RemoveXmlDeclaration(RemoveXmlDeclarationOptimization),
+    /// Remove processing instructions from the SVG document.
+    RemoveXmlProcInst(RemoveXmlProcInstOptimization),
+}
+
+impl Optimization {
+    /// Returns every built-in optimization with its default settings, in the
+    /// order they are meant to be applied.
+    pub fn all() -> Vec<Optimization> {
+        vec![
+            Optimization::RemoveDoctype(RemoveDoctypeOptimization),
+            Optimization::RemoveXmlProcInst(RemoveXmlProcInstOptimization),
+            Optimization::RemoveXmlDeclaration(RemoveXmlDeclarationOptimization),
+            Optimization::RemoveComments(RemoveCommentsOptimization),
+            Optimization::CleanupNumericValues(CleanupNumericValuesOptimization::default()),
+            Optimization::ConvertColors(ConvertColorsOptimization),
+            Optimization::ApplyTransforms(ApplyTransformsOptimization::default()),
+            Optimization::ConvertPathData(ConvertPathDataOptimization::default()),
+            Optimization::ConvertTransform(ConvertTransformOptimization::default()),
+        ]
+    }
+
+    /// Returns `true` if this optimization is performed unless disabled.
+    ///
+    /// Optimizations changing the document, removing content such as
+    /// processing instructions or rounding numbers, are only performed when
+    /// enabled so documents are written back unchanged by default.
+    pub fn is_default(&self) -> bool {
+        match self {
+            Optimization::RemoveComments(_) => false,
+            Optimization::RemoveDoctype(_) => false,
+            Optimization::ConvertPathData(_) => false,
+            Optimization::CleanupNumericValues(_) => false,
+            Optimization::ConvertColors(_) => false,
+            Optimization::ConvertTransform(_) => false,
+            Optimization::ApplyTransforms(_) => false,
+            Optimization::RemoveXmlDeclaration(_) => false,
+            Optimization::RemoveXmlProcInst(_) => false,
+        }
+    }
+
+    /// Sets the number of decimal places kept by optimizations rounding
+    /// numbers, other optimizations are left unchanged.
+    pub fn set_precision(&mut self, precision: Option<u8>) {
+        match self {
+            Optimization::ConvertPathData(optimization) => optimization.precision = precision,
+            Optimization::CleanupNumericValues(optimization) => optimization.precision = precision,
+            Optimization::ConvertTransform(optimization) => optimization.precision = precision,
+            Optimization::ApplyTransforms(optimization) => optimization.precision = precision,
+            _ => {}
+        }
+    }
+
+    /// Returns the [`Plugin`] implementing this optimization
+    fn as_plugin(&self) -> &dyn Plugin {
+        match self {
+            Optimization::RemoveComments(optimization) => optimization,
+            Optimization::RemoveDoctype(optimization) => optimization,
+            Optimization::ConvertPathData(optimization) => optimization,
+            Optimization::CleanupNumericValues(optimization) => optimization,
+            Optimization::ConvertColors(optimization) => optimization,
+            Optimization::ConvertTransform(optimization) => optimization,
+            Optimization::ApplyTransforms(optimization) => optimization,
+            Optimization::RemoveXmlDeclaration(optimization) => optimization,
+            Optimization::RemoveXmlProcInst(optimization) => optimization,
+        }
+    }
 }
 
+impl Plugin for Optimization {
+    fn name(&self) -> &str {
+        self.as_plugin().name()
+    }
+
+    fn description(&self) -> &str {
+        self.as_plugin().description()
+    }
+
+    fn apply(&self, svg: &mut Svg) -> crate::error::Result<()> {
+        self.as_plugin().apply(svg)
+    }
+}
+
+impl FromStr for Optimization {
+    type Err = SvgoError;
+
+    /// Returns the built-in optimization with the provided plugin name and
+    /// its default settings.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::all()
+            .into_iter()
+            .find(|optimization| optimization.name() == s)
+            .ok_or_else(|| SvgoError::UnknownPlugin(s.to_string()))
+    }
+}
+
+/// Default cap on the number of passes performed in multipass mode.
+pub const DEFAULT_MAX_PASSES: usize = 10;
+
+/// Ordered pipeline of [`Plugin`]s applied to a [`Svg`] document.
+///
+/// Plugins run in the order they were appended or inserted. Each plugin name
+/// appears at most once in the pipeline.
 pub struct Optimizer {
-    optimizations: HashSet<Optimization>,
+    plugins: Vec<Box<dyn Plugin>>,
+    /// Maximum number of passes to perform when multipass is enabled.
+    max_passes: Option<usize>,
 }
 
 impl Default for Optimizer {
@@ -28,26 +153,331 @@ impl Default for Optimizer {
 impl Optimizer {
     pub fn new() -> Self {
         Self {
-            optimizations: HashSet::new(),
+            plugins: Vec::new(),
+            max_passes: None,
         }
     }
 
+    /// Enables multipass mode, where the pipeline is applied repeatedly
+    /// until the serialized document stops shrinking or `max_passes` is
+    /// reached. Providing `None` disables multipass mode.
+    pub fn set_multipass(&mut self, max_passes: Option<usize>) {
+        self.max_passes = max_passes;
+    }
+
+    /// Appends a built-in [`Optimization`] to the end of the pipeline.
+    ///
+    /// Refer to [`Optimizer::append_plugin`] for details on duplicates.
     pub fn append(&mut self, optimization: Optimization) {
-        self.optimizations.insert(optimization);
+        self.append_plugin(Box::new(optimization));
     }
 
-    pub fn apply(&self, svg: &mut Svg) -> anyhow::Result<()> {
-        for optimization in &self.optimizations {
-            match optimization {
-                Optimization::RemoveComments(optimization) => {
-                    optimization.apply(svg)?;
-                }
-                Optimization::RemoveDoctype(optimization) => {
-                    optimization.apply(svg)?;
-                }
+    /// Appends a [`Plugin`] to the end of the pipeline.
+    ///
+    /// If a plugin with the same name is already in the pipeline, it is
+    /// replaced in place and keeps its position.
+    pub fn append_plugin(&mut self, plugin: Box<dyn Plugin>) {
+        match self.position(plugin.name()) {
+            Some(index) => self.plugins[index] = plugin,
+            None => self.plugins.push(plugin),
+        }
+    }
+
+    /// Inserts a [`Plugin`] right before the plugin named `anchor`.
+    ///
+    /// A plugin with the same name already in the pipeline is moved to the
+    /// new position, or replaced in place when it is the anchor itself.
+    pub fn insert_before(
+        &mut self,
+        anchor: &str,
+        plugin: Box<dyn Plugin>,
+    ) -> crate::error::Result<()> {
+        self.insert_next_to(anchor, 0, plugin)
+    }
+
+    /// Inserts a [`Plugin`] right after the plugin named `anchor`.
+    ///
+    /// A plugin with the same name already in the pipeline is moved to the
+    /// new position, or replaced in place when it is the anchor itself.
+    pub fn insert_after(
+        &mut self,
+        anchor: &str,
+        plugin: Box<dyn Plugin>,
+    ) -> crate::error::Result<()> {
+        self.insert_next_to(anchor, 1, plugin)
+    }
+
+    /// Removes the plugin with the provided name from the pipeline
+    pub fn remove(&mut self, name: &str) -> Option<Box<dyn Plugin>> {
+        let index = self.position(name)?;
+        Some(self.plugins.remove(index))
+    }
+
+    /// Returns an iterator over the plugins in the order they are applied
+    pub fn plugins(&self) -> impl Iterator<Item = &dyn Plugin> {
+        self.plugins.iter().map(|plugin| plugin.as_ref())
+    }
+
+    /// Applies the pipeline to the provided [`Svg`] document, once or
+    /// repeatedly when multipass mode is enabled.
+    pub fn apply(&self, svg: &mut Svg) -> crate::error::Result<()> {
+        let Some(max_passes) = self.max_passes else {
+            return self.apply_once(svg);
+        };
+
+        let mut size = Self::serialized_size(svg)?;
+
+        for pass in 1..=max_passes.max(1) {
+            self.apply_once(svg)?;
+
+            let next_size = Self::serialized_size(svg)?;
+            tracing::debug!("Optimization pass {} produced {} bytes", pass, next_size);
+
+            if next_size >= size {
+                break;
             }
+
+            size = next_size;
+        }
+
+        Ok(())
+    }
+
+    fn apply_once(&self, svg: &mut Svg) -> crate::error::Result<()> {
+        for plugin in &self.plugins {
+            plugin.apply(svg).map_err(|err| match err {
+                SvgoError::Plugin { .. } => err,
+                err => SvgoError::Plugin {
+                    name: plugin.name().to_string(),
+                    source: Box::new(err),
+                },
+            })?;
         }
 
         Ok(())
     }
+
+    fn serialized_size(svg: &Svg) -> crate::error::Result<usize> {
+        let mut bytes = Vec::new();
+        svg.write(&mut bytes)?;
+        Ok(bytes.len())
+    }
+
+    fn position(&self, name: &str) -> Option<usize> {
+        self.plugins.iter().position(|plugin| plugin.name() == name)
+    }
+
+    fn insert_next_to(
+        &mut self,
+        anchor: &str,
+        offset: usize,
+        plugin: Box<dyn Plugin>,
+    ) -> crate::error::Result<()> {
+        let Some(index) = self.position(anchor) else {
+            return Err(SvgoError::UnknownPlugin(anchor.to_string()));
+        };
+
+        // A plugin inserted next to itself is replaced in place
+        if anchor == plugin.name() {
+            self.plugins[index] = plugin;
+            return Ok(());
+        }
+
+        self.remove(plugin.name());
+
+        let index = self.position(anchor).unwrap_or_default() + offset;
+        self.plugins.insert(index, plugin);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::svg::node::{Attribute, Element, Node};
+
+    struct RemoveIdsPlugin;
+
+    impl Plugin for RemoveIdsPlugin {
+        fn name(&self) -> &str {
+            "remove-ids"
+        }
+
+        fn description(&self) -> &str {
+            "Removes every id attribute"
+        }
+
+        fn apply(&self, svg: &mut Svg) -> crate::error::Result<()> {
+            for node in svg.nodes_mut() {
+                if let Node::Element(element) = node {
+                    element.attributes.retain(
+                        |attr| !matches!(attr, Attribute::Local { key, .. } if key == "id"),
+                    );
+                }
+            }
+
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn applies_custom_plugins_alongside_built_ins() {
+        let mut element = Element::new("svg");
+        element.attributes.push(Attribute::Local {
+            key: "id".to_string(),
+            value: "layer-1".to_string(),
+        });
+        let mut svg = Svg(vec![
+            Node::Comment("Exported by a design tool".to_string()),
+            Node::Element(element),
+        ]);
+
+        let mut optimizer = Optimizer::new();
+        optimizer.append(Optimization::RemoveComments(RemoveCommentsOptimization));
+        optimizer.append_plugin(Box::new(RemoveIdsPlugin));
+        optimizer.apply(&mut svg).unwrap();
+
+        assert_eq!(svg.nodes(), &vec![Node::Element(Element::new("svg"))]);
+    }
+
+    struct NamedPlugin(&'static str);
+
+    impl Plugin for NamedPlugin {
+        fn name(&self) -> &str {
+            self.0
+        }
+
+        fn description(&self) -> &str {
+            "Does nothing"
+        }
+
+        fn apply(&self, _: &mut Svg) -> crate::error::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn keeps_plugins_ordered_and_unique() {
+        let mut optimizer = Optimizer::new();
+        optimizer.append_plugin(Box::new(NamedPlugin("convert-shapes")));
+        optimizer.append_plugin(Box::new(NamedPlugin("merge-paths")));
+        optimizer.append_plugin(Box::new(NamedPlugin("convert-shapes")));
+        optimizer
+            .insert_before("merge-paths", Box::new(NamedPlugin("remove-ids")))
+            .unwrap();
+        optimizer
+            .insert_after("merge-paths", Box::new(NamedPlugin("convert-shapes")))
+            .unwrap();
+
+        assert!(optimizer
+            .insert_after("missing", Box::new(NamedPlugin("other")))
+            .is_err());
+        optimizer
+            .insert_before("remove-ids", Box::new(NamedPlugin("remove-ids")))
+            .unwrap();
+        assert_eq!(
+            optimizer.plugins().map(|p| p.name()).collect::<Vec<_>>(),
+            vec!["remove-ids", "merge-paths", "convert-shapes"]
+        );
+    }
+
+    /// Removes a single comment on every pass, so the document only stops
+    /// shrinking once every comment is gone.
+    struct RemoveFirstCommentPlugin(std::cell::Cell<usize>);
+
+    impl Plugin for RemoveFirstCommentPlugin {
+        fn name(&self) -> &str {
+            "remove-first-comment"
+        }
+
+        fn description(&self) -> &str {
+            "Removes the first comment"
+        }
+
+        fn apply(&self, svg: &mut Svg) -> crate::error::Result<()> {
+            self.0.set(self.0.get() + 1);
+
+            if let Some(index) = svg
+                .nodes()
+                .iter()
+                .position(|node| matches!(node, Node::Comment(_)))
+            {
+                svg.nodes_mut().remove(index);
+            }
+
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn multipass_runs_until_output_stops_shrinking() {
+        let comments = || {
+            Svg(vec![
+                Node::Comment("a".to_string()),
+                Node::Comment("b".to_string()),
+                Node::Comment("c".to_string()),
+                Node::Element(Element::new("svg")),
+            ])
+        };
+
+        let mut svg = comments();
+        let mut optimizer = Optimizer::new();
+        optimizer.append_plugin(Box::new(RemoveFirstCommentPlugin(Default::default())));
+        optimizer.set_multipass(Some(DEFAULT_MAX_PASSES));
+        optimizer.apply(&mut svg).unwrap();
+        assert_eq!(svg.nodes().len(), 1);
+
+        let mut svg = comments();
+        optimizer.set_multipass(Some(2));
+        optimizer.apply(&mut svg).unwrap();
+        assert_eq!(svg.nodes().len(), 2);
+    }
+
+    #[test]
+    fn finds_built_in_optimizations_by_name() {
+        for optimization in Optimization::all() {
+            assert_eq!(
+                optimization.name().parse::<Optimization>().unwrap(),
+                optimization
+            );
+        }
+
+        assert!(matches!(
+            "remove-everything".parse::<Optimization>(),
+            Err(SvgoError::UnknownPlugin(name)) if name == "remove-everything"
+        ));
+    }
+
+    struct UnsupportedPlugin;
+
+    impl Plugin for UnsupportedPlugin {
+        fn name(&self) -> &str {
+            "unsupported"
+        }
+
+        fn description(&self) -> &str {
+            "Always fails"
+        }
+
+        fn apply(&self, _: &mut Svg) -> crate::error::Result<()> {
+            Err(SvgoError::Unsupported(
+                "element <foreignObject>".to_string(),
+            ))
+        }
+    }
+
+    #[test]
+    fn reports_failing_plugin_name() {
+        let mut optimizer = Optimizer::new();
+        optimizer.append_plugin(Box::new(UnsupportedPlugin));
+
+        let err = optimizer.apply(&mut Svg(Vec::new())).unwrap_err();
+
+        assert!(matches!(
+            &err,
+            SvgoError::Plugin { name, source }
+                if name == "unsupported" && source.to_string() == "Unsupported element <foreignObject>"
+        ));
+    }
 }
@@ -0,0 +1,440 @@
+use crate::optimizer::Plugin;
+use crate::svg::node::{Element, Node};
+use crate::svg::number::{format_number, split_number};
+use crate::svg::path::PathData;
+use crate::svg::transform::{Matrix, TransformList};
+use crate::svg::Svg;
+
+use super::convert_path_data::{ConvertPathDataOptimization, DEFAULT_PATH_PRECISION};
+
+/// Properties applied in the user space of the element holding them, which
+/// changes when its transform is applied.
+const USER_SPACE_PROPERTIES: [&str; 3] = ["clip-path", "mask", "filter"];
+
+/// Inherited properties drawing markers, which are scaled along with the
+/// stroke.
+const MARKER_PROPERTIES: [&str; 4] = ["marker", "marker-start", "marker-mid", "marker-end"];
+
+/// Applies transforms directly into path coordinates and removes the
+/// `transform` attribute.
+///
+/// Transforms of groups are pushed down to their children and applied when
+/// every child is a path or a group which can take it. Children with an `id`
+/// may be drawn elsewhere by `<use>`, so their groups are kept.
+///
+/// Paths are skipped when the transform would change how they are painted:
+/// a stroke under a non-uniform scale, a scaled dashed stroke, markers,
+/// gradients or patterns, clipping paths, masks and filters. The stroke
+/// width is scaled along with uniform scales, unless the stroke is drawn
+/// with `vector-effect="non-scaling-stroke"` and is not affected.
+///
+/// Nothing is applied in documents with `<style>` elements, nor to elements
+/// with a `class` or inside one, as their style rules are not known.
+///
+/// Coordinates are rounded to `precision` decimal places, or kept as is when
+/// `precision` is `None`.
+#[derive(Debug, Hash, Eq, PartialEq)]
+pub struct ApplyTransformsOptimization {
+    pub precision: Option<u8>,
+}
+
+impl Default for ApplyTransformsOptimization {
+    fn default() -> Self {
+        Self {
+            precision: Some(DEFAULT_PATH_PRECISION),
+        }
+    }
+}
+
+impl Plugin for ApplyTransformsOptimization {
+    fn name(&self) -> &str {
+        "apply-transforms"
+    }
+
+    fn description(&self) -> &str {
+        "Applies transforms into path coordinates"
+    }
+
+    fn apply(&self, svg: &mut Svg) -> crate::error::Result<()> {
+        if has_style_sheet(svg.nodes()) {
+            return Ok(());
+        }
+
+        self.apply_to_nodes(svg.nodes_mut(), &Context::default());
+        Ok(())
+    }
+}
+
+impl ApplyTransformsOptimization {
+    fn apply_to_nodes(&self, nodes: &mut [Node], context: &Context) {
+        for node in nodes.iter_mut() {
+            let Some(element) = node.as_element_mut() else {
+                continue;
+            };
+
+            if element.attribute("transform").is_some() {
+                // Applied on a copy as a group is only flattened if every
+                // child can take its transform.
+                let mut applied = element.clone();
+
+                if self.absorb(&mut applied, &Matrix::IDENTITY, context)
+                    && attributes_len(&applied) < attributes_len(element)
+                {
+                    *element = applied;
+                    continue;
+                }
+            }
+
+            let context = context.inherit(element);
+            self.apply_to_nodes(&mut element.children, &context);
+        }
+    }
+
+    /// Applies `matrix` followed by the element's own transform into the
+    /// element, returns `false` if the element cannot take it.
+    fn absorb(&self, element: &mut Element, matrix: &Matrix, context: &Context) -> bool {
+        if USER_SPACE_PROPERTIES
+            .iter()
+            .any(|name| property(element, name).is_some_and(|value| value != "none"))
+        {
+            return false;
+        }
+
+        let matrix = match element.attribute("transform").map(TransformList::parse) {
+            Some(Ok(list)) => matrix.multiply(&list.to_matrix()),
+            Some(Err(err)) => {
+                tracing::warn!("Skipping invalid transform: {}", err);
+                return false;
+            }
+            None => *matrix,
+        };
+        let context = context.inherit(element);
+
+        let absorbed = match element.name.as_str() {
+            "path" => self.absorb_path(element, &matrix, &context),
+            "g" => element
+                .children
+                .iter_mut()
+                .filter_map(Node::as_element_mut)
+                .all(|child| {
+                    child.attribute("id").is_none() && self.absorb(child, &matrix, &context)
+                }),
+            _ => false,
+        };
+
+        if absorbed {
+            element.remove_attribute("transform");
+        }
+
+        absorbed
+    }
+
+    fn absorb_path(&self, element: &mut Element, matrix: &Matrix, context: &Context) -> bool {
+        if context.pinned {
+            return false;
+        }
+
+        let Some(Ok(path)) = element.attribute("d").map(PathData::parse) else {
+            return false;
+        };
+
+        // A non-scaling stroke is drawn in screen coordinates, which the
+        // transform doesn't change.
+        let scaled_stroke = property(element, "vector-effect") != Some("non-scaling-stroke");
+
+        if context.stroke && scaled_stroke {
+            let Some(factor) = uniform_scale(matrix) else {
+                return false;
+            };
+
+            if (factor - 1.0).abs() > 1e-9 {
+                if context.dashed {
+                    return false;
+                }
+
+                let width = context.stroke_width.as_deref().unwrap_or("1");
+
+                match split_number(width) {
+                    Some((width, "" | "px")) => {
+                        element.set_attribute(
+                            "stroke-width",
+                            format_number(width * factor, self.precision),
+                        );
+                    }
+                    _ => return false,
+                }
+            }
+        }
+
+        let minifier = ConvertPathDataOptimization {
+            precision: self.precision,
+        };
+        element.set_attribute("d", minifier.minify(&path.transform(matrix)));
+
+        true
+    }
+}
+
+/// Inherited properties affecting whether a transform can be applied
+#[derive(Clone, Debug, Default)]
+struct Context {
+    /// Whether a stroke is painted
+    stroke: bool,
+    stroke_width: Option<String>,
+    /// Whether the stroke is dashed
+    dashed: bool,
+    /// Whether markers or paint servers are used, which are drawn in the
+    /// user space of the element, or a class applies unknown style rules.
+    pinned: bool,
+}
+
+impl Context {
+    fn inherit(&self, element: &Element) -> Context {
+        let mut context = self.clone();
+
+        if let Some(stroke) = property(element, "stroke") {
+            context.stroke = stroke != "none";
+        }
+
+        if let Some(width) = property(element, "stroke-width") {
+            context.stroke_width = Some(width.to_string());
+        }
+
+        if let Some(dasharray) = property(element, "stroke-dasharray") {
+            context.dashed = dasharray != "none";
+        }
+
+        let paint_server = ["fill", "stroke"]
+            .iter()
+            .any(|name| property(element, name).is_some_and(|value| value.starts_with("url(")));
+        let markers = MARKER_PROPERTIES
+            .iter()
+            .any(|name| property(element, name).is_some_and(|value| value != "none"));
+
+        context.pinned |= paint_server || markers || element.attribute("class").is_some();
+        context
+    }
+}
+
+/// Returns the value of a presentation property, inline styles take
+/// precedence over attributes and `inherit` is treated as unset.
+fn property<'a>(element: &'a Element, name: &str) -> Option<&'a str> {
+    let styled = element.attribute("style").and_then(|style| {
+        style
+            .rsplit(';')
+            .filter_map(|declaration| declaration.split_once(':'))
+            .find(|(property, _)| property.trim().eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.trim())
+    });
+
+    styled
+        .or_else(|| element.attribute(name).map(str::trim))
+        .filter(|value| *value != "inherit")
+}
+
+/// Returns whether a `<style>` element is found among `nodes` or their
+/// descendants.
+fn has_style_sheet(nodes: &[Node]) -> bool {
+    nodes
+        .iter()
+        .filter_map(Node::as_element)
+        .any(|element| element.name == "style" || has_style_sheet(&element.children))
+}
+
+/// Returns the scale factor of a matrix made of translations, rotations and
+/// uniform scales, or `None` if it distorts shapes.
+fn uniform_scale(matrix: &Matrix) -> Option<f64> {
+    let Matrix { a, b, c, d, .. } = *matrix;
+    let x = a.hypot(b);
+    let y = c.hypot(d);
+    let tolerance = 1e-9 * x.max(y);
+
+    if (x - y).abs() > tolerance || (a * c + b * d).abs() > tolerance * x.max(y) {
+        return None;
+    }
+
+    Some(x)
+}
+
+/// Returns the number of characters taken by the attributes of an element
+/// and its descendants.
+fn attributes_len(element: &Element) -> usize {
+    let own: usize = element
+        .attributes
+        .iter()
+        .map(|attr| attr.key().len() + attr.value().len())
+        .sum();
+
+    own + element
+        .children
+        .iter()
+        .filter_map(Node::as_element)
+        .map(attributes_len)
+        .sum::<usize>()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::svg::path::Command;
+
+    fn path(d: &str, transform: &str) -> Element {
+        let mut path = Element::new("path");
+        path.set_attribute("d", d);
+        path.set_attribute("transform", transform);
+        path
+    }
+
+    #[test]
+    fn applies_transforms_into_paths() {
+        let mut stroked = path("M 0 0 L 10 0", "scale(2)");
+        stroked.set_attribute("stroke", "#000");
+        stroked.set_attribute("stroke-width", "1.5");
+        let mut distorted = path("M 0 0 L 10 0", "scale(2 1)");
+        distorted.set_attribute("stroke", "#000");
+        let mut svg = Svg(vec![
+            Node::Element(path(
+                "M 10 10 L 20 10 A 5 5 0 0 1 30 10",
+                "translate(10 20)",
+            )),
+            Node::Element(stroked),
+            Node::Element(distorted),
+            Node::Element(path("M 0 0 L 10 0 L 10 10", "matrix(-1 0 0 1 0 0)")),
+        ]);
+
+        ApplyTransformsOptimization::default()
+            .apply(&mut svg)
+            .unwrap();
+
+        let elements: Vec<&Element> = svg.nodes().iter().filter_map(Node::as_element).collect();
+        assert_eq!(elements[0].attribute("d"), Some("m20 30h10a5 5 0 0 1 10 0"));
+        assert_eq!(elements[0].attribute("transform"), None);
+        assert_eq!(elements[1].attribute("d"), Some("m0 0h20"));
+        assert_eq!(elements[1].attribute("stroke-width"), Some("3"));
+        assert_eq!(elements[2].attribute("transform"), Some("scale(2 1)"));
+        assert_eq!(elements[3].attribute("d"), Some("m0 0h-10v10"));
+    }
+
+    #[test]
+    fn keeps_non_scaling_strokes() {
+        let mut path = path("M 0 0 L 10 0", "scale(2 1)");
+        path.set_attribute("stroke", "#000");
+        path.set_attribute("stroke-width", "1.5");
+        path.set_attribute("vector-effect", "non-scaling-stroke");
+        let mut svg = Svg(vec![Node::Element(path)]);
+
+        ApplyTransformsOptimization::default()
+            .apply(&mut svg)
+            .unwrap();
+
+        let path = svg.nodes()[0].as_element().unwrap();
+        assert_eq!(path.attribute("d"), Some("m0 0h20"));
+        assert_eq!(path.attribute("transform"), None);
+        assert_eq!(path.attribute("stroke-width"), Some("1.5"));
+    }
+
+    #[test]
+    fn flattens_group_transforms() {
+        let mut group = Element::new("g");
+        group.set_attribute("transform", "translate(5 5)");
+        group.set_attribute("fill", "red");
+        group
+            .children
+            .push(Node::Element(path("M 0 0 L 10 10", "scale(2)")));
+        let mut clipped = group.clone();
+        clipped.set_attribute("clip-path", "url(#clip)");
+        let mut svg = Svg(vec![Node::Element(group), Node::Element(clipped)]);
+
+        ApplyTransformsOptimization::default()
+            .apply(&mut svg)
+            .unwrap();
+
+        let group = svg.nodes()[0].as_element().unwrap();
+        assert_eq!(group.attribute("transform"), None);
+        assert_eq!(
+            group.children[0].as_element().unwrap().attribute("d"),
+            Some("m5 5 20 20")
+        );
+
+        let clipped = svg.nodes()[1].as_element().unwrap();
+        assert_eq!(clipped.attribute("transform"), Some("translate(5 5)"));
+    }
+
+    #[test]
+    fn keeps_group_transforms_of_children_with_id() {
+        let mut referenced = Element::new("path");
+        referenced.set_attribute("id", "p");
+        referenced.set_attribute("d", "M0 0L10 10");
+        let mut group = Element::new("g");
+        group.set_attribute("transform", "translate(100 0)");
+        group.children.push(Node::Element(referenced));
+        let mut used = Element::new("use");
+        used.set_attribute("xlink:href", "#p");
+        let mut svg = Svg(vec![Node::Element(group), Node::Element(used)]);
+
+        ApplyTransformsOptimization::default()
+            .apply(&mut svg)
+            .unwrap();
+
+        let group = svg.nodes()[0].as_element().unwrap();
+        assert_eq!(group.attribute("transform"), Some("translate(100 0)"));
+        assert_eq!(
+            group.children[0].as_element().unwrap().attribute("d"),
+            Some("M0 0L10 10")
+        );
+    }
+
+    #[test]
+    fn skips_elements_styled_by_style_sheets() {
+        let mut classed = path("M 0 0 L 10 0", "scale(2 1)");
+        classed.set_attribute("class", "outlined");
+        let mut group = Element::new("g");
+        group.set_attribute("class", "outlined");
+        group
+            .children
+            .push(Node::Element(path("M 0 0 L 10 0", "scale(2 1)")));
+        let mut svg = Svg(vec![Node::Element(classed), Node::Element(group)]);
+
+        ApplyTransformsOptimization::default()
+            .apply(&mut svg)
+            .unwrap();
+
+        let classed = svg.nodes()[0].as_element().unwrap();
+        assert_eq!(classed.attribute("transform"), Some("scale(2 1)"));
+        let group = svg.nodes()[1].as_element().unwrap();
+        let child = group.children[0].as_element().unwrap();
+        assert_eq!(child.attribute("transform"), Some("scale(2 1)"));
+
+        let mut style = Element::new("style");
+        style
+            .children
+            .push(Node::Characters(String::from("path { stroke: #000 }")));
+        let mut svg = Svg(vec![
+            Node::Element(style),
+            Node::Element(path("M 0 0 L 10 0", "scale(2 1)")),
+        ]);
+
+        ApplyTransformsOptimization::default()
+            .apply(&mut svg)
+            .unwrap();
+
+        let path = svg.nodes()[1].as_element().unwrap();
+        assert_eq!(path.attribute("transform"), Some("scale(2 1)"));
+    }
+
+    #[test]
+    fn transforms_arcs() {
+        let path = PathData::parse("M 0 0 A 10 5 0 0 1 20 0").unwrap();
+
+        let rotated = path.transform(&Matrix::rotate(90.0));
+        let arc = rotated.segments()[1].command.args();
+        assert!((arc[0] - 10.0).abs() < 1e-9 && (arc[1] - 5.0).abs() < 1e-9);
+        assert!((arc[2].abs() - 90.0).abs() < 1e-9);
+
+        let mirrored = path.transform(&Matrix::scale(-1.0, 1.0));
+        assert!(matches!(
+            mirrored.segments()[1].command,
+            Command::ArcTo { sweep: false, x, .. } if x == -20.0
+        ));
+    }
+}
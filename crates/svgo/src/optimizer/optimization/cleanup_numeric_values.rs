@@ -0,0 +1,190 @@
+use crate::optimizer::Plugin;
+use crate::svg::number::{format_number, split_number};
+use crate::svg::Svg;
+
+/// Attributes holding a single number, optionally followed by a unit
+const NUMERIC_ATTRIBUTES: [&str; 35] = [
+    "x",
+    "y",
+    "x1",
+    "y1",
+    "x2",
+    "y2",
+    "cx",
+    "cy",
+    "dx",
+    "dy",
+    "fx",
+    "fy",
+    "r",
+    "rx",
+    "ry",
+    "width",
+    "height",
+    "refX",
+    "refY",
+    "markerWidth",
+    "markerHeight",
+    "offset",
+    "opacity",
+    "fill-opacity",
+    "stroke-opacity",
+    "stop-opacity",
+    "flood-opacity",
+    "stroke-width",
+    "stroke-miterlimit",
+    "stroke-dashoffset",
+    "font-size",
+    "letter-spacing",
+    "word-spacing",
+    "pathLength",
+    "startOffset",
+];
+
+/// Attributes holding a list of numbers
+const NUMBER_LIST_ATTRIBUTES: [&str; 4] = ["viewBox", "points", "stroke-dasharray", "stdDeviation"];
+
+/// Default number of decimal places kept in numeric values
+pub const DEFAULT_NUMERIC_PRECISION: u8 = 3;
+
+/// Rounds numeric attribute values and writes them in their shortest form.
+///
+/// Leading and trailing zeros are dropped along with the default `px` unit.
+/// Numbers are rounded to `precision` decimal places, or kept as is when
+/// `precision` is `None`.
+#[derive(Debug, Hash, Eq, PartialEq)]
+pub struct CleanupNumericValuesOptimization {
+    pub precision: Option<u8>,
+}
+
+impl Default for CleanupNumericValuesOptimization {
+    fn default() -> Self {
+        Self {
+            precision: Some(DEFAULT_NUMERIC_PRECISION),
+        }
+    }
+}
+
+impl Plugin for CleanupNumericValuesOptimization {
+    fn name(&self) -> &str {
+        "cleanup-numeric-values"
+    }
+
+    fn description(&self) -> &str {
+        "Rounds numeric values and writes them in their shortest form"
+    }
+
+    fn apply(&self, svg: &mut Svg) -> crate::error::Result<()> {
+        svg.for_each_element_mut(|element| {
+            for attr in element.attributes.iter_mut() {
+                let key = attr.key();
+                let value = attr.value().trim();
+
+                let cleaned = if NUMERIC_ATTRIBUTES.contains(&key) {
+                    self.cleanup_number(value)
+                } else if NUMBER_LIST_ATTRIBUTES.contains(&key) {
+                    self.cleanup_number_list(value)
+                } else {
+                    None
+                };
+
+                if let Some(cleaned) = cleaned {
+                    *attr.value_mut() = cleaned;
+                }
+            }
+        });
+
+        Ok(())
+    }
+}
+
+impl CleanupNumericValuesOptimization {
+    /// Writes a number followed by an optional unit in its shortest form,
+    /// returns `None` for values which are not numeric.
+    ///
+    /// The number is kept as written unless the formatted one is shorter, as
+    /// exponents such as `1e21` are longer once expanded.
+    fn cleanup_number(&self, value: &str) -> Option<String> {
+        let (number, unit) = split_number(value)?;
+        let written = &value[..value.len() - unit.len()];
+        let unit = match unit {
+            "px" => "",
+            "" | "%" | "em" | "ex" | "pt" | "pc" | "mm" | "cm" | "in" => unit,
+            _ => return None,
+        };
+
+        let formatted = format_number(number, self.precision);
+        let number = match formatted.len() < written.len() {
+            true => formatted.as_str(),
+            false => written,
+        };
+
+        Some(format!("{}{}", number, unit))
+    }
+
+    /// Writes a list of numbers separated by whitespace and/or commas with
+    /// each number in its shortest form.
+    fn cleanup_number_list(&self, value: &str) -> Option<String> {
+        let numbers = value
+            .split(|ch: char| ch == ',' || ch.is_ascii_whitespace())
+            .filter(|number| !number.is_empty())
+            .map(|number| self.cleanup_number(number))
+            .collect::<Option<Vec<String>>>()?;
+
+        Some(numbers.join(" "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::svg::node::{Element, Node};
+
+    #[test]
+    fn cleans_up_numeric_attributes() {
+        let mut rect = Element::new("rect");
+        rect.set_attribute("x", "0.50");
+        rect.set_attribute("width", "120.00px");
+        rect.set_attribute("height", "50%");
+        rect.set_attribute("stroke-width", "1.23456");
+        rect.set_attribute("viewBox", "0, 0, 120.0 , 120.0");
+        rect.set_attribute("points", "-0.5,1.25 3,4");
+        rect.set_attribute("stroke-dasharray", "none");
+        rect.set_attribute("id", "0.50");
+        let mut svg = Svg(vec![Node::Element(rect)]);
+
+        let optimization = CleanupNumericValuesOptimization::default();
+        optimization.apply(&mut svg).unwrap();
+
+        let rect = svg.root().unwrap().element().unwrap();
+        assert_eq!(rect.attribute("x"), Some(".5"));
+        assert_eq!(rect.attribute("width"), Some("120"));
+        assert_eq!(rect.attribute("height"), Some("50%"));
+        assert_eq!(rect.attribute("stroke-width"), Some("1.235"));
+        assert_eq!(rect.attribute("viewBox"), Some("0 0 120 120"));
+        assert_eq!(rect.attribute("points"), Some("-.5 1.25 3 4"));
+        assert_eq!(rect.attribute("stroke-dasharray"), Some("none"));
+        assert_eq!(rect.attribute("id"), Some("0.50"));
+    }
+
+    #[test]
+    fn keeps_exponents_shorter_than_their_expansion() {
+        let mut rect = Element::new("rect");
+        rect.set_attribute("x", "1e21");
+        rect.set_attribute("y", "1e-7");
+        rect.set_attribute("width", "1e21px");
+        rect.set_attribute("height", "1.5e2");
+        rect.set_attribute("viewBox", "0 0 1e21 2.50e1");
+        let mut svg = Svg(vec![Node::Element(rect)]);
+
+        let optimization = CleanupNumericValuesOptimization { precision: None };
+        optimization.apply(&mut svg).unwrap();
+
+        let rect = svg.root().unwrap().element().unwrap();
+        assert_eq!(rect.attribute("x"), Some("1e21"));
+        assert_eq!(rect.attribute("y"), Some("1e-7"));
+        assert_eq!(rect.attribute("width"), Some("1e21"));
+        assert_eq!(rect.attribute("height"), Some("150"));
+        assert_eq!(rect.attribute("viewBox"), Some("0 0 1e21 25"));
+    }
+}
@@ -0,0 +1,117 @@
+use crate::optimizer::Plugin;
+use crate::svg::color::Color;
+use crate::svg::Svg;
+
+/// Properties holding a color, either as attributes or in inline styles
+const COLOR_PROPERTIES: [&str; 5] = [
+    "fill",
+    "stroke",
+    "stop-color",
+    "flood-color",
+    "lighting-color",
+];
+
+/// Rewrites colors in their shortest form, e.g. `rgb(255,0,0)` and
+/// `#ff0000` as `red` or `#aabbcc` as `#abc`.
+#[derive(Debug, Default, Hash, Eq, PartialEq)]
+pub struct ConvertColorsOptimization;
+
+impl Plugin for ConvertColorsOptimization {
+    fn name(&self) -> &str {
+        "convert-colors"
+    }
+
+    fn description(&self) -> &str {
+        "Rewrites colors in their shortest form"
+    }
+
+    fn apply(&self, svg: &mut Svg) -> crate::error::Result<()> {
+        svg.for_each_element_mut(|element| {
+            for attr in element.attributes.iter_mut() {
+                let converted = match attr.key() {
+                    "style" => convert_style(attr.value()),
+                    key if COLOR_PROPERTIES.contains(&key) => convert_color(attr.value()),
+                    _ => None,
+                };
+
+                if let Some(converted) = converted {
+                    *attr.value_mut() = converted;
+                }
+            }
+        });
+
+        Ok(())
+    }
+}
+
+/// Returns the shortest form of a color value if it differs from `value`
+fn convert_color(value: &str) -> Option<String> {
+    let shortest = Color::parse(value)?.to_shortest_string()?;
+
+    if shortest.len() <= value.len() && shortest != value {
+        return Some(shortest);
+    }
+
+    None
+}
+
+/// Rewrites colors in the declarations of an inline `style` attribute,
+/// returns `None` if no color was rewritten.
+fn convert_style(style: &str) -> Option<String> {
+    let mut changed = false;
+    let declarations: Vec<String> = style
+        .split(';')
+        .map(|declaration| {
+            let Some((property, value)) = declaration.split_once(':') else {
+                return declaration.to_string();
+            };
+
+            if !COLOR_PROPERTIES.contains(&property.trim().to_ascii_lowercase().as_str()) {
+                return declaration.to_string();
+            }
+
+            match convert_color(value.trim()) {
+                Some(converted) => {
+                    changed = true;
+                    let leading = &value[..value.len() - value.trim_start().len()];
+                    let trailing = &value[value.trim_end().len()..];
+                    format!("{}:{}{}{}", property, leading, converted, trailing)
+                }
+                None => declaration.to_string(),
+            }
+        })
+        .collect();
+
+    changed.then(|| declarations.join(";"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::svg::node::{Element, Node};
+
+    #[test]
+    fn converts_colors_in_attributes_and_styles() {
+        let mut rect = Element::new("rect");
+        rect.set_attribute("fill", "rgb(255,0,0)");
+        rect.set_attribute("stroke", "#AABBCC");
+        rect.set_attribute("stop-color", "#000080");
+        rect.set_attribute("flood-color", "currentColor");
+        rect.set_attribute("lighting-color", "rgba(0,0,0,.5)");
+        rect.set_attribute("style", "fill: #ff0000; stroke:hsl(0,0%,100%);opacity:1");
+        let mut svg = Svg(vec![Node::Element(rect)]);
+
+        ConvertColorsOptimization.apply(&mut svg).unwrap();
+
+        let rect = svg.root().unwrap().element().unwrap();
+        assert_eq!(rect.attribute("fill"), Some("red"));
+        assert_eq!(rect.attribute("stroke"), Some("#abc"));
+        assert_eq!(rect.attribute("stop-color"), Some("navy"));
+        assert_eq!(rect.attribute("flood-color"), Some("currentColor"));
+        assert_eq!(rect.attribute("lighting-color"), Some("rgba(0,0,0,.5)"));
+        assert_eq!(
+            rect.attribute("style"),
+            Some("fill: red; stroke:#fff;opacity:1")
+        );
+    }
+}
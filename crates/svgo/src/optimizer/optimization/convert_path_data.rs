@@ -0,0 +1,415 @@
+use crate::optimizer::Plugin;
+use crate::svg::number::{format_number, round};
+use crate::svg::path::{Command, PathData};
+use crate::svg::Svg;
+
+/// Elements whose `d` attribute holds path data
+const PATH_ELEMENTS: [&str; 3] = ["path", "glyph", "missing-glyph"];
+
+/// Default number of decimal places kept in path data coordinates
+pub const DEFAULT_PATH_PRECISION: u8 = 3;
+
+/// Rewrites path data in its shortest form.
+///
+/// Each segment is written with either absolute or relative coordinates,
+/// whichever is shorter. Repeated command letters and unneeded separators are
+/// dropped, lines are collapsed into horizontal and vertical lines, and
+/// curves are written using their smooth shorthands when possible.
+///
+/// Coordinates are rounded to `precision` decimal places, or kept as is when
+/// `precision` is `None`.
+#[derive(Debug, Hash, Eq, PartialEq)]
+pub struct ConvertPathDataOptimization {
+    pub precision: Option<u8>,
+}
+
+impl Default for ConvertPathDataOptimization {
+    fn default() -> Self {
+        Self {
+            precision: Some(DEFAULT_PATH_PRECISION),
+        }
+    }
+}
+
+impl Plugin for ConvertPathDataOptimization {
+    fn name(&self) -> &str {
+        "convert-path-data"
+    }
+
+    fn description(&self) -> &str {
+        "Rewrites path data in its shortest form"
+    }
+
+    fn apply(&self, svg: &mut Svg) -> crate::error::Result<()> {
+        svg.for_each_element_mut(|element| {
+            if !PATH_ELEMENTS.contains(&element.name.as_str()) {
+                return;
+            }
+
+            let Some(value) = element.attribute("d") else {
+                return;
+            };
+
+            match PathData::parse(value) {
+                Ok(path) => {
+                    let minified = self.minify(&path);
+
+                    if minified.len() < value.len() {
+                        element.set_attribute("d", minified);
+                    }
+                }
+                Err(err) => match element.attribute_span("d") {
+                    Some(span) => {
+                        tracing::warn!("Skipping invalid path data at {}: {}", span.start, err)
+                    }
+                    None => tracing::warn!("Skipping invalid path data: {}", err),
+                },
+            }
+        });
+
+        Ok(())
+    }
+}
+
+impl ConvertPathDataOptimization {
+    /// Writes the provided path data in its shortest form
+    pub fn minify(&self, path: &PathData) -> String {
+        let mut out = PathWriter::default();
+        // Points as they are computed by a reader of the written path data,
+        // which may differ from the original ones due to rounding.
+        let mut current = (0.0, 0.0);
+        let mut start = (0.0, 0.0);
+        let mut cubic_control: Option<(f64, f64)> = None;
+        let mut quadratic_control: Option<(f64, f64)> = None;
+
+        for segment in path.to_absolute().segments() {
+            let mut candidates: Vec<Candidate> = Vec::new();
+            let (cx, cy) = current;
+
+            match segment.command {
+                Command::MoveTo { x, y } => {
+                    for (letter, args, points) in self.forms('M', current, &[(x, y)]) {
+                        candidates.push(Candidate::new(letter, args, points[0]));
+                    }
+                }
+                Command::LineTo { x, y } => {
+                    if self.same(y, cy) {
+                        for (letter, args, points) in self.forms('H', current, &[(x, cy)]) {
+                            candidates.push(Candidate::new(
+                                letter,
+                                vec![args[0]],
+                                (points[0].0, cy),
+                            ));
+                        }
+                    }
+
+                    if self.same(x, cx) {
+                        for (letter, args, points) in self.forms('V', current, &[(cx, y)]) {
+                            candidates.push(Candidate::new(
+                                letter,
+                                vec![args[1]],
+                                (cx, points[0].1),
+                            ));
+                        }
+                    }
+
+                    for (letter, args, points) in self.forms('L', current, &[(x, y)]) {
+                        candidates.push(Candidate::new(letter, args, points[0]));
+                    }
+                }
+                Command::CurveTo {
+                    x1,
+                    y1,
+                    x2,
+                    y2,
+                    x,
+                    y,
+                } => {
+                    let (rx, ry) = reflect(current, cubic_control);
+                    let smooth = self.same(x1, rx) && self.same(y1, ry);
+
+                    for (letter, args, points) in
+                        self.forms('C', current, &[(x1, y1), (x2, y2), (x, y)])
+                    {
+                        if smooth {
+                            let letter = if letter == 'c' { 's' } else { 'S' };
+                            candidates.push(
+                                Candidate::new(letter, args[2..].to_vec(), points[2])
+                                    .cubic(points[1]),
+                            );
+                        }
+
+                        candidates.push(Candidate::new(letter, args, points[2]).cubic(points[1]));
+                    }
+                }
+                Command::QuadraticCurveTo { x1, y1, x, y } => {
+                    let (rx, ry) = reflect(current, quadratic_control);
+                    let smooth = self.same(x1, rx) && self.same(y1, ry);
+
+                    for (letter, args, points) in self.forms('Q', current, &[(x1, y1), (x, y)]) {
+                        if smooth {
+                            let letter = if letter == 'q' { 't' } else { 'T' };
+                            candidates.push(
+                                Candidate::new(letter, args[2..].to_vec(), points[1])
+                                    .quadratic((rx, ry)),
+                            );
+                        }
+
+                        candidates
+                            .push(Candidate::new(letter, args, points[1]).quadratic(points[0]));
+                    }
+                }
+                Command::ArcTo {
+                    rx,
+                    ry,
+                    x_axis_rotation,
+                    large_arc,
+                    sweep,
+                    x,
+                    y,
+                } => {
+                    let radii = [rx, ry, x_axis_rotation].map(|value| self.absolute(value));
+                    let flags = [large_arc, sweep].map(|flag| f64::from(u8::from(flag)));
+
+                    for (letter, coordinates, points) in self.forms('A', current, &[(x, y)]) {
+                        let mut args = radii.to_vec();
+                        args.extend(flags);
+                        args.extend(coordinates);
+                        candidates.push(Candidate::new(letter, args, points[0]));
+                    }
+                }
+                Command::ClosePath => {
+                    candidates.push(Candidate::new('z', Vec::new(), start));
+                }
+                _ => unreachable!("Absolute path data only holds full commands"),
+            }
+
+            let Some(candidate) = candidates
+                .into_iter()
+                .min_by_key(|candidate| out.cost(candidate.letter, &candidate.args))
+            else {
+                continue;
+            };
+
+            out.push(candidate.letter, &candidate.args);
+            current = candidate.end;
+            cubic_control = candidate.cubic_control;
+            quadratic_control = candidate.quadratic_control;
+
+            if candidate.letter.eq_ignore_ascii_case(&'M') {
+                start = current;
+            }
+        }
+
+        out.text
+    }
+
+    /// Returns the relative and absolute forms of a segment going through
+    /// `points`, along with the flattened coordinates and the points a reader
+    /// computes from each form.
+    ///
+    /// The relative form is skipped when no precision is set and it cannot
+    /// reproduce every point exactly.
+    #[allow(clippy::type_complexity)]
+    fn forms(
+        &self,
+        letter: char,
+        (cx, cy): (f64, f64),
+        points: &[(f64, f64)],
+    ) -> Vec<(char, Vec<f64>, Vec<(f64, f64)>)> {
+        let mut forms = Vec::with_capacity(2);
+        let relative: Vec<(f64, f64)> = points
+            .iter()
+            .map(|&(x, y)| (self.relative(x, cx), self.relative(y, cy)))
+            .collect();
+        let relative_points: Vec<(f64, f64)> = relative
+            .iter()
+            .map(|&(dx, dy)| (cx + dx, cy + dy))
+            .collect();
+
+        if self.precision.is_some() || relative_points == points {
+            let args = relative.iter().flat_map(|&(dx, dy)| [dx, dy]).collect();
+            forms.push((letter.to_ascii_lowercase(), args, relative_points));
+        }
+
+        let absolute: Vec<(f64, f64)> = points
+            .iter()
+            .map(|&(x, y)| (self.absolute(x), self.absolute(y)))
+            .collect();
+        let args = absolute.iter().flat_map(|&(x, y)| [x, y]).collect();
+        forms.push((letter, args, absolute));
+
+        forms
+    }
+
+    fn absolute(&self, value: f64) -> f64 {
+        match self.precision {
+            Some(precision) => round(value, precision),
+            None => value,
+        }
+    }
+
+    fn relative(&self, value: f64, origin: f64) -> f64 {
+        self.absolute(value - origin)
+    }
+
+    /// Determines whether two coordinates are written the same way
+    fn same(&self, a: f64, b: f64) -> bool {
+        self.relative(a, b) == 0.0
+    }
+}
+
+/// Reflects `control` about `point`, or returns `point` if there is no
+/// control point to reflect.
+fn reflect(point: (f64, f64), control: Option<(f64, f64)>) -> (f64, f64) {
+    match control {
+        Some((x, y)) => (2.0 * point.0 - x, 2.0 * point.1 - y),
+        None => point,
+    }
+}
+
+/// A way of writing a path segment
+struct Candidate {
+    letter: char,
+    args: Vec<f64>,
+    end: (f64, f64),
+    cubic_control: Option<(f64, f64)>,
+    quadratic_control: Option<(f64, f64)>,
+}
+
+impl Candidate {
+    fn new(letter: char, args: Vec<f64>, end: (f64, f64)) -> Self {
+        Self {
+            letter,
+            args,
+            end,
+            cubic_control: None,
+            quadratic_control: None,
+        }
+    }
+
+    fn cubic(mut self, control: (f64, f64)) -> Self {
+        self.cubic_control = Some(control);
+        self
+    }
+
+    fn quadratic(mut self, control: (f64, f64)) -> Self {
+        self.quadratic_control = Some(control);
+        self
+    }
+}
+
+/// Writes path segments using as few characters as possible
+#[derive(Default)]
+struct PathWriter {
+    text: String,
+    /// Letter a command can omit when it is repeated, coordinates following
+    /// a move to are implicitly line to commands.
+    implicit_letter: Option<char>,
+    /// Whether the last written token is a number, and if it has a decimal
+    /// point.
+    last_number: Option<bool>,
+}
+
+impl PathWriter {
+    fn cost(&self, letter: char, args: &[f64]) -> usize {
+        self.render(letter, args).0.len()
+    }
+
+    fn push(&mut self, letter: char, args: &[f64]) {
+        let (text, last_number) = self.render(letter, args);
+
+        self.text.push_str(&text);
+        self.last_number = last_number;
+        self.implicit_letter = match letter {
+            'M' => Some('L'),
+            'm' => Some('l'),
+            'z' | 'Z' => None,
+            _ => Some(letter),
+        };
+    }
+
+    fn render(&self, letter: char, args: &[f64]) -> (String, Option<bool>) {
+        let mut text = String::new();
+        let mut last_number = None;
+
+        if !args.is_empty() && self.implicit_letter == Some(letter) {
+            last_number = self.last_number;
+        } else {
+            text.push(letter);
+        }
+
+        for arg in args {
+            let number = format_number(*arg, None);
+
+            if let Some(has_decimal_point) = last_number {
+                let implicit_separator =
+                    number.starts_with('-') || (number.starts_with('.') && has_decimal_point);
+
+                if !implicit_separator {
+                    text.push(' ');
+                }
+            }
+
+            last_number = Some(number.contains('.'));
+            text.push_str(&number);
+        }
+
+        (text, last_number)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::svg::node::{Element, Node};
+
+    #[test]
+    fn minifies_path_data() {
+        let paths = vec![
+            ("M 10 10 L 20 10 L 20 20 L 10 20 Z", "m10 10h10v10H10z"),
+            ("M 0.5 0.5 L -0.5 -0.5 L 1.5 1.5", "m.5.5-1-1 2 2"),
+            (
+                "M 10 10 C 20 20 30 20 40 10 C 50 0 60 0 70 10",
+                "m10 10c10 10 20 10 30 0s20-10 30 0",
+            ),
+            ("M 0 0 Q 10 10 20 0 Q 30 -10 40 0", "m0 0q10 10 20 0t20 0"),
+            (
+                "M 100 100 A 25 25 0 0 1 150 100",
+                "m100 100a25 25 0 0 1 50 0",
+            ),
+            (
+                "M 1.23456 2.34567 L 3.45678 4.56789",
+                "m1.235 2.346 2.222 2.222",
+            ),
+        ];
+
+        let optimization = ConvertPathDataOptimization::default();
+
+        for (value, expected) in paths {
+            let path = PathData::parse(value).unwrap();
+            assert_eq!(optimization.minify(&path), expected);
+            assert_eq!(
+                PathData::parse(expected)
+                    .unwrap()
+                    .to_absolute()
+                    .segments()
+                    .len(),
+                path.segments().len()
+            );
+        }
+    }
+
+    #[test]
+    fn rewrites_d_attribute_of_paths() {
+        let mut path = Element::new("path");
+        path.set_attribute("d", "M 10 10 L 20 10");
+        let mut svg = Svg(vec![Node::Element(path)]);
+
+        let optimization = ConvertPathDataOptimization { precision: None };
+        optimization.apply(&mut svg).unwrap();
+
+        let path = svg.root().unwrap().element().unwrap();
+        assert_eq!(path.attribute("d"), Some("m10 10h10"));
+    }
+}
@@ -0,0 +1,211 @@
+use crate::optimizer::Plugin;
+use crate::svg::transform::{Matrix, Transform, TransformList};
+use crate::svg::Svg;
+
+/// Attributes holding a transform list
+const TRANSFORM_ATTRIBUTES: [&str; 3] = ["transform", "gradientTransform", "patternTransform"];
+
+/// Default number of decimal places kept in transform values
+pub const DEFAULT_TRANSFORM_PRECISION: u8 = 5;
+
+/// Collapses transform lists into their shortest equivalent form.
+///
+/// Transform lists are combined into a single matrix which is then written
+/// as either `translate`, `scale`, `rotate`, a combination of them or
+/// `matrix`, whichever is shorter. Identity transforms are removed.
+///
+/// Numbers are rounded to `precision` decimal places, or kept as is when
+/// `precision` is `None`.
+#[derive(Debug, Hash, Eq, PartialEq)]
+pub struct ConvertTransformOptimization {
+    pub precision: Option<u8>,
+}
+
+impl Default for ConvertTransformOptimization {
+    fn default() -> Self {
+        Self {
+            precision: Some(DEFAULT_TRANSFORM_PRECISION),
+        }
+    }
+}
+
+impl Plugin for ConvertTransformOptimization {
+    fn name(&self) -> &str {
+        "convert-transform"
+    }
+
+    fn description(&self) -> &str {
+        "Collapses transforms into their shortest form"
+    }
+
+    fn apply(&self, svg: &mut Svg) -> crate::error::Result<()> {
+        svg.for_each_element_mut(|element| {
+            for name in TRANSFORM_ATTRIBUTES {
+                let Some(value) = element.attribute(name) else {
+                    continue;
+                };
+
+                let list = match TransformList::parse(value) {
+                    Ok(list) => list,
+                    Err(err) => {
+                        match element.attribute_span(name) {
+                            Some(span) => {
+                                tracing::warn!(
+                                    "Skipping invalid transform at {}: {}",
+                                    span.start,
+                                    err
+                                )
+                            }
+                            None => tracing::warn!("Skipping invalid transform: {}", err),
+                        }
+
+                        continue;
+                    }
+                };
+
+                let converted = self.minify(&list.to_matrix());
+
+                if converted.is_empty() {
+                    element.remove_attribute(name);
+                } else if converted.len() < value.len() {
+                    element.set_attribute(name, converted);
+                }
+            }
+        });
+
+        Ok(())
+    }
+}
+
+impl ConvertTransformOptimization {
+    /// Writes the transformation described by `matrix` in its shortest
+    /// form, which is an empty string for the identity transform.
+    pub fn minify(&self, matrix: &Matrix) -> String {
+        Self::candidates(matrix)
+            .iter()
+            .map(|transforms| {
+                transforms
+                    .iter()
+                    .map(|transform| transform.to_string_with_precision(self.precision))
+                    .collect::<Vec<String>>()
+                    .join(" ")
+            })
+            .filter(|text| self.is_equivalent(text, matrix))
+            .min_by_key(|text| text.len())
+            .unwrap_or_else(|| Transform::Matrix(*matrix).to_string())
+    }
+
+    /// Returns the transform lists which may be equivalent to `matrix`,
+    /// candidates are verified once written as they may not hold for every
+    /// matrix.
+    fn candidates(matrix: &Matrix) -> Vec<Vec<Transform>> {
+        let Matrix { a, b, c, d, e, f } = *matrix;
+        let translate = Transform::Translate { tx: e, ty: f };
+        let scale = Transform::Scale { sx: a, sy: d };
+        let mut candidates = vec![
+            Vec::new(),
+            vec![Transform::Matrix(*matrix)],
+            vec![translate],
+            vec![scale],
+            vec![translate, scale],
+        ];
+
+        // Rotation, optionally combined with a uniform scale
+        let factor = a.hypot(b);
+
+        if factor > 0.0 && (c + b).abs() < f64::EPSILON.max(factor * 1e-12) {
+            let angle = b.atan2(a).to_degrees();
+            let rotate = Transform::Rotate {
+                angle,
+                cx: 0.0,
+                cy: 0.0,
+            };
+            let uniform = Transform::Scale {
+                sx: factor,
+                sy: factor,
+            };
+
+            // Solve the center of rotation from the translation components
+            let (sin, cos) = angle.to_radians().sin_cos();
+            let determinant = 2.0 * (1.0 - cos);
+
+            if determinant.abs() > f64::EPSILON {
+                let cx = ((1.0 - cos) * e - sin * f) / determinant;
+                let cy = (sin * e + (1.0 - cos) * f) / determinant;
+                candidates.push(vec![Transform::Rotate { angle, cx, cy }]);
+            }
+
+            candidates.push(vec![rotate]);
+            candidates.push(vec![translate, rotate]);
+            candidates.push(vec![rotate, uniform]);
+            candidates.push(vec![translate, rotate, uniform]);
+        }
+
+        candidates
+    }
+
+    /// Determines whether the written transform list produces `matrix`
+    /// within the error allowed by the precision.
+    fn is_equivalent(&self, text: &str, matrix: &Matrix) -> bool {
+        let Ok(list) = TransformList::parse(text) else {
+            return false;
+        };
+
+        let tolerance = match self.precision {
+            Some(precision) => 2.0 * 10_f64.powi(-i32::from(precision)),
+            None => 1e-9,
+        };
+
+        list.to_matrix()
+            .to_array()
+            .iter()
+            .zip(matrix.to_array())
+            .all(|(a, b)| (a - b).abs() <= tolerance)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::svg::node::{Element, Node};
+
+    #[test]
+    fn collapses_transforms_into_shortest_form() {
+        let transforms = vec![
+            ("translate(10 20) translate(-10 -20)", ""),
+            ("matrix(1 0 0 1 10 0)", "translate(10)"),
+            ("translate(0 0) scale(2, 2)", "scale(2)"),
+            ("translate(10 20) scale(2 3)", "matrix(2 0 0 3 10 20)"),
+            ("rotate(90 10 10)", "rotate(90 10 10)"),
+            (
+                "translate(10 10) rotate(45) translate(-10 -10)",
+                "rotate(45 10 10)",
+            ),
+            ("matrix(0 2 -2 0 0 0)", "rotate(90) scale(2)"),
+            ("skewX(45)", "matrix(1 0 1 1 0 0)"),
+        ];
+
+        let optimization = ConvertTransformOptimization::default();
+
+        for (value, expected) in transforms {
+            let matrix = TransformList::parse(value).unwrap().to_matrix();
+            assert_eq!(optimization.minify(&matrix), expected, "{}", value);
+        }
+    }
+
+    #[test]
+    fn removes_identity_transforms() {
+        let mut group = Element::new("g");
+        group.set_attribute("transform", "scale(1)");
+        group.set_attribute("gradientTransform", "matrix(1,0,0,1,5.000000,0)");
+        let mut svg = Svg(vec![Node::Element(group)]);
+
+        ConvertTransformOptimization::default()
+            .apply(&mut svg)
+            .unwrap();
+
+        let group = svg.root().unwrap().element().unwrap();
+        assert_eq!(group.attribute("transform"), None);
+        assert_eq!(group.attribute("gradientTransform"), Some("translate(5)"));
+    }
+}
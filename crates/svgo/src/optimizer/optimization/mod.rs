@@ -1,5 +1,19 @@
+pub mod apply_transforms;
+pub mod cleanup_numeric_values;
+pub mod convert_colors;
+pub mod convert_path_data;
+pub mod convert_transform;
 pub mod remove_comments;
 pub mod remove_doctype;
+pub mod remove_xml_declaration;
+pub mod remove_xml_proc_inst;
 
+pub use apply_transforms::ApplyTransformsOptimization;
+pub use cleanup_numeric_values::CleanupNumericValuesOptimization;
+pub use convert_colors::ConvertColorsOptimization;
+pub use convert_path_data::ConvertPathDataOptimization;
+pub use convert_transform::ConvertTransformOptimization;
 pub use remove_comments::RemoveCommentsOptimization;
 pub use remove_doctype::RemoveDoctypeOptimization;
+pub use remove_xml_declaration::RemoveXmlDeclarationOptimization;
+pub use remove_xml_proc_inst::RemoveXmlProcInstOptimization;
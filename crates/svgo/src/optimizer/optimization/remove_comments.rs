@@ -1,11 +1,20 @@
+use crate::optimizer::Plugin;
 use crate::svg::{node::Node, Svg};
 
 #[derive(Debug, Default, Hash, Eq, PartialEq)]
 pub struct RemoveCommentsOptimization;
 
-impl RemoveCommentsOptimization {
-    pub fn apply(&self, svg: &mut Svg) -> anyhow::Result<()> {
-        svg.0.retain(|node| !matches!(node, Node::Comment(_)));
+impl Plugin for RemoveCommentsOptimization {
+    fn name(&self) -> &str {
+        "remove-comments"
+    }
+
+    fn description(&self) -> &str {
+        "Removes comments from the SVG document"
+    }
+
+    fn apply(&self, svg: &mut Svg) -> crate::error::Result<()> {
+        svg.retain(|node| !matches!(node, Node::Comment(_)));
 
         Ok(())
     }
@@ -14,6 +23,7 @@ impl RemoveCommentsOptimization {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::svg::node::Element;
 
     #[test]
     fn removes_comments_from_nodes_vector() {
@@ -27,4 +37,21 @@ mod tests {
 
         assert_eq!(svg.0.len(), 0);
     }
+
+    #[test]
+    fn removes_comments_from_nested_elements() {
+        let mut group = Element::new("g");
+        group.children = vec![
+            Node::Comment("Nested comment".to_string()),
+            Node::Element(Element::new("path")),
+        ];
+        let mut svg = Svg(vec![Node::Element(group)]);
+
+        let optimization = RemoveCommentsOptimization;
+        optimization.apply(&mut svg).unwrap();
+
+        let group = svg.root().unwrap();
+        assert_eq!(group.children().count(), 1);
+        assert_eq!(group.first_child().unwrap().element().unwrap().name, "path");
+    }
 }
@@ -1,11 +1,20 @@
+use crate::optimizer::Plugin;
 use crate::svg::{node::Node, Svg};
 
 #[derive(Debug, Default, Hash, Eq, PartialEq)]
 pub struct RemoveDoctypeOptimization;
 
-impl RemoveDoctypeOptimization {
-    pub fn apply(&self, svg: &mut Svg) -> anyhow::Result<()> {
-        svg.0.retain(|node| !matches!(node, Node::Doctype(_)));
+impl Plugin for RemoveDoctypeOptimization {
+    fn name(&self) -> &str {
+        "remove-doctype"
+    }
+
+    fn description(&self) -> &str {
+        "Removes the DOCTYPE declaration from the SVG document"
+    }
+
+    fn apply(&self, svg: &mut Svg) -> crate::error::Result<()> {
+        svg.retain(|node| !matches!(node, Node::Doctype(_)));
 
         Ok(())
     }
@@ -0,0 +1,61 @@
+use crate::optimizer::Plugin;
+use crate::svg::{node::Node, Svg};
+
+/// Removes the `<?xml ...?>` declaration, along with the whitespace
+/// following it.
+#[derive(Debug, Default, Hash, Eq, PartialEq)]
+pub struct RemoveXmlDeclarationOptimization;
+
+impl Plugin for RemoveXmlDeclarationOptimization {
+    fn name(&self) -> &str {
+        "remove-xml-declaration"
+    }
+
+    fn description(&self) -> &str {
+        "Removes the XML declaration from the SVG document"
+    }
+
+    fn apply(&self, svg: &mut Svg) -> crate::error::Result<()> {
+        let nodes = svg.nodes_mut();
+
+        let Some(index) = nodes
+            .iter()
+            .position(|node| matches!(node, Node::Declaration { .. }))
+        else {
+            return Ok(());
+        };
+
+        nodes.remove(index);
+
+        if let Some(Node::Characters(value)) = nodes.get(index) {
+            if value.trim().is_empty() {
+                nodes.remove(index);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::svg::node::{Element, Version};
+
+    #[test]
+    fn removes_xml_declaration() {
+        let mut svg = Svg(vec![
+            Node::Declaration {
+                version: Version::Version10,
+                encoding: Some("utf-8".to_string()),
+                standalone: None,
+            },
+            Node::Characters("\n".to_string()),
+            Node::Element(Element::new("svg")),
+        ]);
+
+        RemoveXmlDeclarationOptimization.apply(&mut svg).unwrap();
+
+        assert_eq!(svg.nodes(), &vec![Node::Element(Element::new("svg"))]);
+    }
+}
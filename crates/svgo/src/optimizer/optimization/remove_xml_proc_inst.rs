@@ -0,0 +1,43 @@
+use crate::optimizer::Plugin;
+use crate::svg::{node::Node, Svg};
+
+/// Removes processing instructions such as `<?xml-stylesheet?>`
+#[derive(Debug, Default, Hash, Eq, PartialEq)]
+pub struct RemoveXmlProcInstOptimization;
+
+impl Plugin for RemoveXmlProcInstOptimization {
+    fn name(&self) -> &str {
+        "remove-xml-proc-inst"
+    }
+
+    fn description(&self) -> &str {
+        "Removes processing instructions from the SVG document"
+    }
+
+    fn apply(&self, svg: &mut Svg) -> crate::error::Result<()> {
+        svg.retain(|node| !matches!(node, Node::ProcessingInstruction { .. }));
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::svg::node::Element;
+
+    #[test]
+    fn removes_processing_instructions() {
+        let mut svg = Svg(vec![
+            Node::ProcessingInstruction {
+                name: "xml-stylesheet".to_string(),
+                data: Some(r#"href="a.css""#.to_string()),
+            },
+            Node::Element(Element::new("svg")),
+        ]);
+
+        RemoveXmlProcInstOptimization.apply(&mut svg).unwrap();
+
+        assert_eq!(svg.nodes(), &vec![Node::Element(Element::new("svg"))]);
+    }
+}
@@ -0,0 +1,19 @@
+//! Extension point for SVG optimizations
+
+use crate::svg::Svg;
+
+/// An optimization performed over a [`Svg`] document.
+///
+/// Every built-in optimization implements this trait, and downstream crates
+/// can implement it to register their own optimizations on an
+/// [`Optimizer`](super::Optimizer) alongside the built-in ones.
+pub trait Plugin {
+    /// Name which identifies this plugin, e.g. `remove-comments`
+    fn name(&self) -> &str;
+
+    /// Short, human readable description of what this plugin does
+    fn description(&self) -> &str;
+
+    /// Performs the optimization on the provided [`Svg`] document
+    fn apply(&self, svg: &mut Svg) -> crate::error::Result<()>;
+}
@@ -0,0 +1,270 @@
+//! Colors in SVG paint and style values
+//! Read more: https://www.w3.org/TR/css-color-3/
+
+mod names;
+
+use std::fmt::Display;
+
+use super::number::split_number;
+
+use self::names::NAMED_COLORS;
+
+/// A color in the sRGB color space with an alpha channel
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Color {
+    pub red: u8,
+    pub green: u8,
+    pub blue: u8,
+    /// Opacity of the color, between `0.0` and `1.0`
+    pub alpha: f64,
+}
+
+impl Color {
+    /// Creates an opaque color from its red, green and blue components
+    pub fn rgb(red: u8, green: u8, blue: u8) -> Self {
+        Self {
+            red,
+            green,
+            blue,
+            alpha: 1.0,
+        }
+    }
+
+    /// Parses a color written as a name, in hexadecimal notation or using
+    /// the `rgb()`, `rgba()`, `hsl()` or `hsla()` functions.
+    ///
+    /// Returns `None` for values which are not colors, such as `none`,
+    /// `currentColor` or `url(#gradient)`.
+    pub fn parse(value: &str) -> Option<Self> {
+        let value = value.trim().to_ascii_lowercase();
+
+        if let Some(hex) = value.strip_prefix('#') {
+            return Self::parse_hex(hex);
+        }
+
+        if let Some((function, args)) = value.strip_suffix(')').and_then(|v| v.split_once('(')) {
+            let args: Vec<&str> = args
+                .split(|ch: char| ch == ',' || ch == '/' || ch.is_ascii_whitespace())
+                .filter(|arg| !arg.is_empty())
+                .collect();
+
+            return match function.trim() {
+                "rgb" | "rgba" => Self::parse_rgb(&args),
+                "hsl" | "hsla" => Self::parse_hsl(&args),
+                _ => None,
+            };
+        }
+
+        NAMED_COLORS
+            .binary_search_by_key(&value.as_str(), |(name, _)| name)
+            .ok()
+            .map(|index| {
+                let [red, green, blue] = NAMED_COLORS[index].1;
+                Self::rgb(red, green, blue)
+            })
+    }
+
+    /// Returns `true` if this color has no transparency
+    pub fn is_opaque(&self) -> bool {
+        self.alpha >= 1.0
+    }
+
+    /// Returns the shortest way to write this color, choosing between its
+    /// name and hexadecimal notations.
+    ///
+    /// Returns `None` for translucent colors, which can't be written using
+    /// either notation in SVG 1.1.
+    pub fn to_shortest_string(&self) -> Option<String> {
+        if !self.is_opaque() {
+            return None;
+        }
+
+        let hex = self.to_string();
+        let name = NAMED_COLORS
+            .iter()
+            .filter(|(_, rgb)| *rgb == [self.red, self.green, self.blue])
+            .map(|(name, _)| *name)
+            .min_by_key(|name| name.len());
+
+        match name {
+            Some(name) if name.len() < hex.len() => Some(name.to_string()),
+            _ => Some(hex),
+        }
+    }
+
+    fn parse_hex(hex: &str) -> Option<Self> {
+        if !hex.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+            return None;
+        }
+
+        let digit = |index: usize| u8::from_str_radix(&hex[index..=index], 16).ok();
+        let pair = |index: usize| u8::from_str_radix(&hex[index..index + 2], 16).ok();
+
+        let (components, alpha) = match hex.len() {
+            3 | 4 => {
+                let short = |index| digit(index).map(|value| value * 17);
+                let alpha = if hex.len() == 4 { short(3)? } else { 255 };
+                ([short(0)?, short(1)?, short(2)?], alpha)
+            }
+            6 | 8 => {
+                let alpha = if hex.len() == 8 { pair(6)? } else { 255 };
+                ([pair(0)?, pair(2)?, pair(4)?], alpha)
+            }
+            _ => return None,
+        };
+
+        let [red, green, blue] = components;
+        Some(Self {
+            red,
+            green,
+            blue,
+            alpha: f64::from(alpha) / 255.0,
+        })
+    }
+
+    fn parse_rgb(args: &[&str]) -> Option<Self> {
+        let (rgb, alpha) = Self::split_alpha(args)?;
+        let mut components = [0_u8; 3];
+
+        for (component, arg) in components.iter_mut().zip(rgb) {
+            let value = match split_number(arg)? {
+                (number, "") => number,
+                (number, "%") => number / 100.0 * 255.0,
+                _ => return None,
+            };
+
+            *component = value.round().clamp(0.0, 255.0) as u8;
+        }
+
+        let [red, green, blue] = components;
+        Some(Self {
+            red,
+            green,
+            blue,
+            alpha,
+        })
+    }
+
+    fn parse_hsl(args: &[&str]) -> Option<Self> {
+        let (hsl, alpha) = Self::split_alpha(args)?;
+
+        let hue = match split_number(hsl[0])? {
+            (number, "" | "deg") => number,
+            _ => return None,
+        };
+        let percentage = |arg: &str| match split_number(arg)? {
+            (number, "%") => Some((number / 100.0).clamp(0.0, 1.0)),
+            _ => None,
+        };
+        let saturation = percentage(hsl[1])?;
+        let lightness = percentage(hsl[2])?;
+
+        // Read more: https://www.w3.org/TR/css-color-3/#hsl-color
+        let hue = hue.rem_euclid(360.0) / 360.0;
+        let m2 = if lightness <= 0.5 {
+            lightness * (saturation + 1.0)
+        } else {
+            lightness + saturation - lightness * saturation
+        };
+        let m1 = lightness * 2.0 - m2;
+        let channel = |hue: f64| {
+            let hue = hue.rem_euclid(1.0);
+            let value = if hue * 6.0 < 1.0 {
+                m1 + (m2 - m1) * hue * 6.0
+            } else if hue * 2.0 < 1.0 {
+                m2
+            } else if hue * 3.0 < 2.0 {
+                m1 + (m2 - m1) * (2.0 / 3.0 - hue) * 6.0
+            } else {
+                m1
+            };
+
+            (value * 255.0).round().clamp(0.0, 255.0) as u8
+        };
+
+        Some(Self {
+            red: channel(hue + 1.0 / 3.0),
+            green: channel(hue),
+            blue: channel(hue - 1.0 / 3.0),
+            alpha,
+        })
+    }
+
+    /// Splits the arguments of a color function into its three components
+    /// and its alpha, which defaults to `1.0`.
+    fn split_alpha<'a>(args: &'a [&'a str]) -> Option<(&'a [&'a str], f64)> {
+        match args {
+            [_, _, _] => Some((args, 1.0)),
+            [components @ .., alpha] if components.len() == 3 => {
+                let alpha = match split_number(alpha)? {
+                    (number, "") => number,
+                    (number, "%") => number / 100.0,
+                    _ => return None,
+                };
+
+                Some((components, alpha.clamp(0.0, 1.0)))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl Display for Color {
+    /// Writes the color in hexadecimal notation, using the three digit form
+    /// when possible. Alpha is not written.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let components = [self.red, self.green, self.blue];
+
+        if components.iter().all(|value| value % 17 == 0) {
+            let [red, green, blue] = components.map(|value| value / 17);
+            write!(f, "#{:x}{:x}{:x}", red, green, blue)
+        } else {
+            write!(f, "#{:02x}{:02x}{:02x}", self.red, self.green, self.blue)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_colors() {
+        let colors = vec![
+            ("red", Some(Color::rgb(255, 0, 0))),
+            ("ReD", Some(Color::rgb(255, 0, 0))),
+            ("#F00", Some(Color::rgb(255, 0, 0))),
+            ("#aabbcc", Some(Color::rgb(170, 187, 204))),
+            ("rgb(255, 0, 0)", Some(Color::rgb(255, 0, 0))),
+            ("rgb(100%,50%,0%)", Some(Color::rgb(255, 128, 0))),
+            ("hsl(120, 100%, 25%)", Some(Color::rgb(0, 128, 0))),
+            ("hsl(0 0% 100%)", Some(Color::rgb(255, 255, 255))),
+            ("none", None),
+            ("currentColor", None),
+            ("url(#gradient)", None),
+            ("#ggg", None),
+        ];
+
+        for (value, expected) in colors {
+            assert_eq!(Color::parse(value), expected, "{}", value);
+        }
+
+        let translucent = Color::parse("rgba(0, 0, 0, .5)").unwrap();
+        assert_eq!(translucent.alpha, 0.5);
+    }
+
+    #[test]
+    fn writes_colors_in_shortest_form() {
+        let colors = vec![
+            (Color::rgb(255, 0, 0), "red"),
+            (Color::rgb(170, 187, 204), "#abc"),
+            (Color::rgb(0, 0, 128), "navy"),
+            (Color::rgb(255, 255, 255), "#fff"),
+            (Color::rgb(18, 52, 86), "#123456"),
+        ];
+
+        for (color, expected) in colors {
+            assert_eq!(color.to_shortest_string().unwrap(), expected);
+        }
+    }
+}
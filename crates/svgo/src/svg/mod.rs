@@ -1,14 +1,20 @@
+pub mod color;
 pub mod node;
+pub mod number;
 pub mod parser;
+pub mod path;
+pub mod raw;
+pub mod span;
+pub mod transform;
 pub mod writer;
 
 use std::fs::File;
 
-use anyhow::Result;
+use crate::error::Result;
 
-use self::node::Node;
-use self::parser::Parser;
-use self::writer::Writer;
+use self::node::{Node, NodeRef};
+use self::parser::{ParseError, Parser};
+use self::writer::{Writer, WriterOptions};
 
 pub struct Svg(pub(crate) Vec<Node>);
 
@@ -28,14 +34,58 @@ impl Svg {
         Ok(Self(nodes))
     }
 
+    /// Reads a SVG file using the provided [`Parser`], returning the errors
+    /// recovered from along with the document.
+    pub fn read_with<R: std::io::Read>(r: R, parser: &Parser) -> Result<(Self, Vec<ParseError>)> {
+        let parsed = parser.parse(r)?;
+        Ok((Self(parsed.nodes), parsed.warnings))
+    }
+
     /// Writes the SVG document to a [`std::io::Write`] instance.
     pub fn write<W: std::io::Write>(&self, write: W) -> Result<()> {
         Writer::write(write, &self.0)
     }
 
-    /// Returns the nodes in the SVG document
+    /// Writes the SVG document to a [`std::io::Write`] instance using the
+    /// provided [`WriterOptions`].
+    pub fn write_with<W: std::io::Write>(&self, write: W, options: &WriterOptions) -> Result<()> {
+        Writer::write_with(write, &self.0, options)
+    }
+
+    /// Returns the top-level nodes in the SVG document
     #[inline]
     pub fn nodes(&self) -> &Vec<Node> {
         &self.0
     }
+
+    /// Returns a mutable reference to the top-level nodes in the SVG document
+    #[inline]
+    pub fn nodes_mut(&mut self) -> &mut Vec<Node> {
+        &mut self.0
+    }
+
+    /// Returns a [`NodeRef`] to the root element of the SVG document, usually
+    /// the `<svg>` tag.
+    pub fn root(&self) -> Option<NodeRef<'_>> {
+        let index = self.0.iter().position(|node| node.as_element().is_some())?;
+        NodeRef::new(&self.0, vec![index])
+    }
+
+    /// Returns a [`NodeRef`] to the node found following the provided path of
+    /// child indices, starting from the top-level nodes.
+    pub fn get(&self, path: &[usize]) -> Option<NodeRef<'_>> {
+        NodeRef::new(&self.0, path.to_vec())
+    }
+
+    /// Calls `f` on every element of the SVG document, parents being visited
+    /// before their children.
+    pub fn for_each_element_mut<F: FnMut(&mut node::Element)>(&mut self, mut f: F) {
+        node::for_each_element_mut(&mut self.0, &mut f);
+    }
+
+    /// Retains only the nodes specified by the predicate, at every depth of
+    /// the document tree.
+    pub fn retain<F: FnMut(&Node) -> bool>(&mut self, mut f: F) {
+        node::retain_deep(&mut self.0, &mut f);
+    }
 }
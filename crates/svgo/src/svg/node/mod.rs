@@ -1,9 +1,18 @@
 //! XML Nodes and Elements for SVG documents
 //! Read more: https://developer.mozilla.org/en-US/docs/Web/XML/XML_introduction
 
+mod tree;
+
 use std::fmt::{Debug, Display};
 use std::str::FromStr;
 
+pub use self::tree::NodeRef;
+
+use crate::error::SvgoError;
+
+use super::raw::RawElement;
+use super::span::{self, ElementSpans, Span};
+
 const XML_VERSION_1_0: &str = "1.0";
 const XML_VERSION_1_1: &str = "1.1";
 
@@ -16,13 +25,13 @@ pub enum Version {
 }
 
 impl FromStr for Version {
-    type Err = anyhow::Error;
+    type Err = SvgoError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s {
             XML_VERSION_1_0 => Ok(Self::Version10),
             XML_VERSION_1_1 => Ok(Self::Version11),
-            _ => Err(anyhow::anyhow!("Invalid XML version")),
+            _ => Err(SvgoError::Unsupported(format!("XML version \"{}\"", s))),
         }
     }
 }
@@ -62,6 +71,7 @@ pub enum Attribute {
         namespace: String,
         prefix: Option<String>,
     },
+    /// Namespace declaration, such as `xmlns` or `xmlns:xlink`
     Declaration {
         key: String,
         value: String,
@@ -87,6 +97,51 @@ impl Display for Attribute {
     }
 }
 
+impl Attribute {
+    /// Returns the name of this attribute without its prefix
+    pub fn key(&self) -> &str {
+        match self {
+            Self::Local { key, .. }
+            | Self::Namespaced { key, .. }
+            | Self::Declaration { key, .. } => key,
+        }
+    }
+
+    /// Returns the value of this attribute
+    pub fn value(&self) -> &str {
+        match self {
+            Self::Local { value, .. }
+            | Self::Namespaced { value, .. }
+            | Self::Declaration { value, .. } => value,
+        }
+    }
+
+    /// Returns a mutable reference to the value of this attribute
+    pub fn value_mut(&mut self) -> &mut String {
+        match self {
+            Self::Local { value, .. }
+            | Self::Namespaced { value, .. }
+            | Self::Declaration { value, .. } => value,
+        }
+    }
+
+    /// Returns `true` if this attribute is written with the provided name,
+    /// namespaced attributes are matched by their qualified name, e.g.
+    /// `xlink:href`.
+    pub fn is(&self, name: &str) -> bool {
+        match self {
+            Self::Namespaced {
+                key,
+                prefix: Some(prefix),
+                ..
+            } => name
+                .split_once(':')
+                .is_some_and(|(p, k)| p == prefix && k == key),
+            _ => self.key() == name,
+        }
+    }
+}
+
 impl From<xml::attribute::OwnedAttribute> for Attribute {
     fn from(value: xml::attribute::OwnedAttribute) -> Self {
         if let Some(prefix) = value.name.prefix {
@@ -105,34 +160,131 @@ impl From<xml::attribute::OwnedAttribute> for Attribute {
     }
 }
 
-/// Determines wether an element is an opening or closing tag
-#[derive(Clone, Debug, PartialEq, Eq)]
-pub enum ElementType {
-    Open,
-    Close,
-}
-
 /// Elements/Tags in a SVG document tree
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Element {
+    /// Name of this element without its prefix
     pub name: String,
+    /// Prefix this element is written with, e.g. `inkscape` for
+    /// `<inkscape:grid>`
+    pub prefix: Option<String>,
+    /// URI of the namespace this element belongs to
+    pub namespace: Option<String>,
     pub attributes: Vec<Attribute>,
-    pub r#type: ElementType,
+    /// Nodes enclosed by this element's opening and closing tags
+    pub children: Vec<Node>,
+    /// Locations of this element, its attributes and its children in the
+    /// source document, only recorded when enabled with [`Parser::spans`].
+    ///
+    /// [`Parser::spans`]: crate::svg::parser::Parser::spans
+    pub spans: Option<Box<ElementSpans>>,
+    /// Source text of this element, only recorded when enabled with
+    /// [`Parser::lossless`].
+    ///
+    /// [`Parser::lossless`]: crate::svg::parser::Parser::lossless
+    pub raw: Option<Box<RawElement>>,
+}
+
+impl Element {
+    /// Creates an [`Element`] with the provided name and no attributes or
+    /// children.
+    pub fn new<S: Into<String>>(name: S) -> Self {
+        Self {
+            name: name.into(),
+            prefix: None,
+            namespace: None,
+            attributes: Vec::new(),
+            children: Vec::new(),
+            spans: None,
+            raw: None,
+        }
+    }
+
+    /// Returns the name this element is written with, including its prefix
+    pub fn qualified_name(&self) -> String {
+        match &self.prefix {
+            Some(prefix) => format!("{}:{}", prefix, self.name),
+            None => self.name.clone(),
+        }
+    }
+
+    /// Returns the location of this element in the source document, if
+    /// recorded.
+    pub fn span(&self) -> Option<Span> {
+        self.spans.as_ref().map(|spans| spans.element)
+    }
+
+    /// Returns the location of the child at `index` in the source document,
+    /// if recorded.
+    pub fn child_span(&self, index: usize) -> Option<Span> {
+        span::node_span(
+            &self.children,
+            index,
+            self.spans.as_ref().map(|spans| &spans.children),
+        )
+    }
+
+    /// Returns the location of the attribute with the provided qualified
+    /// name in the source document, if recorded.
+    pub fn attribute_span(&self, name: &str) -> Option<Span> {
+        self.spans
+            .as_ref()?
+            .attributes
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, span)| *span)
+    }
+
+    /// Returns the value of the attribute with the provided name, refer to
+    /// [`Attribute::is`] for details on how names are matched.
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|attr| attr.is(name))
+            .map(Attribute::value)
+    }
+
+    /// Sets the value of the attribute with the provided name, appending it
+    /// as a [`Attribute::Local`] if not present.
+    pub fn set_attribute<S: Into<String>>(&mut self, name: &str, value: S) {
+        match self.attributes.iter_mut().find(|attr| attr.is(name)) {
+            Some(attr) => *attr.value_mut() = value.into(),
+            None => self.attributes.push(Attribute::Local {
+                key: name.to_string(),
+                value: value.into(),
+            }),
+        }
+    }
+
+    /// Removes the attribute with the provided name, returning its value
+    pub fn remove_attribute(&mut self, name: &str) -> Option<String> {
+        let index = self.attributes.iter().position(|attr| attr.is(name))?;
+        let attr = self.attributes.remove(index);
+        Some(attr.value().to_string())
+    }
 }
 
 /// Nodes in a SVG document
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub enum Node {
     /// XML - declaration is not a tag. It is used for the transmission
     /// of the meta-data of a document.
     Declaration {
         /// Used version XML in this document.
         version: Version,
-        /// Used encoding in this document.
-        encoding: String,
+        /// Used encoding in this document, if declared.
+        encoding: Option<String>,
+        /// Value of the `standalone` pseudo-attribute, if declared.
+        standalone: Option<bool>,
     },
     /// The `DOCTYPE` declaration in a SVG document
     Doctype(String),
+    /// Processing instruction such as `<?xml-stylesheet href="a.css"?>`
+    ProcessingInstruction {
+        /// Target of the instruction, e.g. `xml-stylesheet`
+        name: String,
+        data: Option<String>,
+    },
     /// Tag element in a SVG document
     Element(Element),
     /// `CDATA` section in a SVG document
@@ -143,6 +295,60 @@ pub enum Node {
     Characters(String),
 }
 
+impl Node {
+    /// Returns the [`Element`] if this node is an element
+    pub fn as_element(&self) -> Option<&Element> {
+        match self {
+            Self::Element(element) => Some(element),
+            _ => None,
+        }
+    }
+
+    /// Returns a mutable reference to the [`Element`] if this node is an
+    /// element
+    pub fn as_element_mut(&mut self) -> Option<&mut Element> {
+        match self {
+            Self::Element(element) => Some(element),
+            _ => None,
+        }
+    }
+
+    /// Returns the children of this node, which is an empty slice for every
+    /// node other than [`Node::Element`].
+    pub fn children(&self) -> &[Node] {
+        match self {
+            Self::Element(element) => &element.children,
+            _ => &[],
+        }
+    }
+}
+
+/// Calls `f` on every element in `nodes` and their descendants, parents
+/// being visited before their children.
+pub(crate) fn for_each_element_mut<F: FnMut(&mut Element)>(nodes: &mut [Node], f: &mut F) {
+    for node in nodes {
+        if let Node::Element(element) = node {
+            f(element);
+            for_each_element_mut(&mut element.children, f);
+        }
+    }
+}
+
+/// Retains only the nodes specified by the predicate at every depth of the
+/// tree.
+///
+/// Children of a removed element are removed along with it and are not
+/// visited.
+pub(crate) fn retain_deep<F: FnMut(&Node) -> bool>(nodes: &mut Vec<Node>, f: &mut F) {
+    nodes.retain(|node| f(node));
+
+    for node in nodes.iter_mut() {
+        if let Node::Element(element) = node {
+            retain_deep(&mut element.children, f);
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::Attribute;
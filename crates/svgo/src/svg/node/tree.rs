@@ -0,0 +1,171 @@
+//! Navigation over the SVG document tree
+
+use super::{Element, Node};
+use crate::svg::span::{self, Span};
+
+/// A reference to a [`Node`] in a document tree which keeps track of its
+/// position, allowing navigation to parent and sibling nodes.
+///
+/// The position is stored as the path of child indices from the top-level
+/// nodes of the document down to the referenced node.
+#[derive(Clone, Debug)]
+pub struct NodeRef<'a> {
+    root: &'a [Node],
+    path: Vec<usize>,
+}
+
+impl<'a> NodeRef<'a> {
+    /// Creates a [`NodeRef`] for the node found at `path` in `root`, if any.
+    pub(crate) fn new(root: &'a [Node], path: Vec<usize>) -> Option<Self> {
+        let node_ref = Self { root, path };
+
+        if node_ref.path.is_empty() || node_ref.siblings().len() <= node_ref.index() {
+            return None;
+        }
+
+        Some(node_ref)
+    }
+
+    /// Returns the referenced [`Node`]
+    pub fn node(&self) -> &'a Node {
+        &self.siblings()[self.index()]
+    }
+
+    /// Returns the referenced [`Element`] if the node is an element
+    pub fn element(&self) -> Option<&'a Element> {
+        self.node().as_element()
+    }
+
+    /// Returns the location of this node in the source document, if
+    /// recorded.
+    pub fn span(&self) -> Option<Span> {
+        match self.parent() {
+            Some(parent) => parent.element()?.child_span(self.index()),
+            None => {
+                let root = self.root.iter().find_map(Node::as_element);
+                let siblings = root.and_then(|root| Some(&root.spans.as_ref()?.siblings));
+                span::node_span(self.root, self.index(), siblings)
+            }
+        }
+    }
+
+    /// Returns the path of child indices leading to this node
+    pub fn path(&self) -> &[usize] {
+        &self.path
+    }
+
+    /// Returns the number of ancestors of this node, top-level nodes have
+    /// a depth of `0`.
+    pub fn depth(&self) -> usize {
+        self.path.len() - 1
+    }
+
+    /// Returns the element enclosing this node, or `None` for top-level nodes
+    pub fn parent(&self) -> Option<Self> {
+        if self.path.len() < 2 {
+            return None;
+        }
+
+        let path = self.path[..self.path.len() - 1].to_vec();
+        Some(Self {
+            root: self.root,
+            path,
+        })
+    }
+
+    /// Returns the node right before this one under the same parent
+    pub fn prev_sibling(&self) -> Option<Self> {
+        let index = self.index().checked_sub(1)?;
+        self.sibling(index)
+    }
+
+    /// Returns the node right after this one under the same parent
+    pub fn next_sibling(&self) -> Option<Self> {
+        self.sibling(self.index() + 1)
+    }
+
+    /// Returns the first child of this node
+    pub fn first_child(&self) -> Option<Self> {
+        self.child(0)
+    }
+
+    /// Returns the last child of this node
+    pub fn last_child(&self) -> Option<Self> {
+        let index = self.node().children().len().checked_sub(1)?;
+        self.child(index)
+    }
+
+    /// Returns an iterator over the children of this node
+    pub fn children(&self) -> impl Iterator<Item = NodeRef<'a>> + '_ {
+        (0..self.node().children().len()).filter_map(move |index| self.child(index))
+    }
+
+    /// Returns an iterator over the ancestors of this node, starting with
+    /// its parent.
+    pub fn ancestors(&self) -> impl Iterator<Item = NodeRef<'a>> {
+        std::iter::successors(self.parent(), |node| node.parent())
+    }
+
+    fn child(&self, index: usize) -> Option<Self> {
+        let mut path = self.path.clone();
+        path.push(index);
+        Self::new(self.root, path)
+    }
+
+    fn sibling(&self, index: usize) -> Option<Self> {
+        let mut path = self.path.clone();
+        *path.last_mut()? = index;
+        Self::new(self.root, path)
+    }
+
+    fn index(&self) -> usize {
+        self.path[self.path.len() - 1]
+    }
+
+    fn siblings(&self) -> &'a [Node] {
+        let mut nodes = self.root;
+
+        for index in &self.path[..self.path.len() - 1] {
+            nodes = match nodes.get(*index) {
+                Some(node) => node.children(),
+                None => &[],
+            };
+        }
+
+        nodes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn navigates_parent_and_siblings() {
+        let mut group = Element::new("g");
+        group.children = vec![
+            Node::Element(Element::new("rect")),
+            Node::Comment("between".to_string()),
+            Node::Element(Element::new("circle")),
+        ];
+        let mut svg = Element::new("svg");
+        svg.children = vec![Node::Element(group)];
+        let nodes = vec![Node::Element(svg)];
+
+        let rect = NodeRef::new(&nodes, vec![0, 0, 0]).unwrap();
+        assert_eq!(rect.element().unwrap().name, "rect");
+        assert_eq!(rect.depth(), 2);
+        assert!(rect.prev_sibling().is_none());
+
+        let circle = rect.next_sibling().unwrap().next_sibling().unwrap();
+        assert_eq!(circle.element().unwrap().name, "circle");
+        assert!(circle.next_sibling().is_none());
+
+        let group = circle.parent().unwrap();
+        assert_eq!(group.element().unwrap().name, "g");
+        assert_eq!(group.children().count(), 3);
+        assert_eq!(group.last_child().unwrap().path(), circle.path());
+        assert_eq!(circle.ancestors().count(), 2);
+        assert!(NodeRef::new(&nodes, vec![0, 1]).is_none());
+    }
+}
@@ -0,0 +1,112 @@
+//! Numbers in SVG attribute values
+
+/// Rounds `value` to the provided number of decimal places
+pub fn round(value: f64, precision: u8) -> f64 {
+    let factor = 10_f64.powi(i32::from(precision));
+    let rounded = (value * factor).round() / factor;
+
+    if rounded.is_finite() {
+        rounded
+    } else {
+        value
+    }
+}
+
+/// Formats a number in its shortest form, rounding it to `precision` decimal
+/// places when provided.
+///
+/// Leading zeros are dropped, so `0.5` is written as `.5` and `-0.5` as
+/// `-.5`.
+pub fn format_number(value: f64, precision: Option<u8>) -> String {
+    let value = match precision {
+        Some(precision) => round(value, precision),
+        None => value,
+    };
+
+    // Also covers negative zero
+    if value == 0.0 {
+        return String::from("0");
+    }
+
+    let text = value.to_string();
+
+    if let Some(fraction) = text.strip_prefix("0.") {
+        format!(".{}", fraction)
+    } else if let Some(fraction) = text.strip_prefix("-0.") {
+        format!("-.{}", fraction)
+    } else {
+        text
+    }
+}
+
+/// Splits `value` into its leading number and the remainder, usually a unit
+/// such as `px` or `%`. Returns `None` if `value` doesn't begin with a number.
+pub fn split_number(value: &str) -> Option<(f64, &str)> {
+    let bytes = value.as_bytes();
+    let digits_from = |mut index: usize| {
+        while bytes.get(index).is_some_and(u8::is_ascii_digit) {
+            index += 1;
+        }
+
+        index
+    };
+
+    let mut end = usize::from(matches!(bytes.first(), Some(b'-' | b'+')));
+    let integer_end = digits_from(end);
+    let mut has_digits = integer_end > end;
+    end = integer_end;
+
+    if bytes.get(end) == Some(&b'.') {
+        let fraction_end = digits_from(end + 1);
+        has_digits |= fraction_end > end + 1;
+        end = fraction_end;
+    }
+
+    if !has_digits {
+        return None;
+    }
+
+    if matches!(bytes.get(end), Some(b'e' | b'E')) {
+        let sign = usize::from(matches!(bytes.get(end + 1), Some(b'-' | b'+')));
+        let exponent_end = digits_from(end + 1 + sign);
+
+        // An `e` not followed by digits belongs to a unit such as `em`
+        if exponent_end > end + 1 + sign {
+            end = exponent_end;
+        }
+    }
+
+    let number = value[..end].parse().ok()?;
+    Some((number, &value[end..]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_numbers_in_shortest_form() {
+        let numbers = vec![
+            (0.5, None, ".5"),
+            (-0.5, None, "-.5"),
+            (-0.0, None, "0"),
+            (10.0, None, "10"),
+            (1.23456, Some(3), "1.235"),
+            (0.1 + 0.2, Some(3), ".3"),
+            (-0.0001, Some(3), "0"),
+            (1250.0, Some(0), "1250"),
+        ];
+
+        for (value, precision, expected) in numbers {
+            assert_eq!(format_number(value, precision), expected);
+        }
+    }
+
+    #[test]
+    fn splits_numbers_from_units() {
+        assert_eq!(split_number("10px"), Some((10.0, "px")));
+        assert_eq!(split_number("-.5em"), Some((-0.5, "em")));
+        assert_eq!(split_number("1e2%"), Some((100.0, "%")));
+        assert_eq!(split_number("auto"), None);
+    }
+}
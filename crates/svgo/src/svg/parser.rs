@@ -1,50 +1,296 @@
 //! XML Parser for SVG documents
 
-use std::io::{BufReader, Read};
+use std::fmt::Display;
+use std::io::Read;
 
-use anyhow::Result;
-use xml::{reader::XmlEvent, ParserConfig};
+use crate::error::Result;
+use xml::{
+    attribute::OwnedAttribute,
+    common::Position,
+    namespace::{Namespace, NS_NO_PREFIX, NS_XMLNS_PREFIX, NS_XML_PREFIX},
+    reader::XmlEvent,
+    ParserConfig,
+};
 
-use super::node::{Attribute, Element, ElementType, Node};
+use super::node::{Attribute, Element, Node};
+use super::raw::{Lexer, RawElement, RawNodes, TokenKind};
+use super::span::{ElementSpans, NodeSpans, SourceMap, Span};
 
-#[derive(Debug)]
-pub struct Parser;
+/// Maximum number of characters kept in the snippet of a [`ParseError`]
+const SNIPPET_LENGTH: usize = 60;
+
+/// Error found when parsing a malformed SVG document
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    /// Line where the error was found, starting at 1
+    pub line: u64,
+    /// Column where the error was found, starting at 1
+    pub column: u64,
+    /// Construct being parsed when the error was found, e.g. `<path>`
+    pub construct: String,
+    pub message: String,
+    /// Source around the position where the error was found
+    pub snippet: String,
+}
+
+impl ParseError {
+    fn new(err: &xml::reader::Error, source: &[u8], open: &[Element]) -> Self {
+        let position = err.position();
+        let construct = match open.last() {
+            Some(element) => format!("<{}>", element.qualified_name()),
+            None => "document".to_string(),
+        };
+        let snippet = String::from_utf8_lossy(source)
+            .lines()
+            .nth(position.row as usize)
+            .map(|line| {
+                let start = (position.column as usize).saturating_sub(SNIPPET_LENGTH / 2);
+                line.chars()
+                    .skip(start)
+                    .take(SNIPPET_LENGTH)
+                    .collect::<String>()
+            })
+            .unwrap_or_default();
+
+        Self {
+            line: position.row + 1,
+            column: position.column + 1,
+            construct,
+            message: err.msg().to_string(),
+            snippet: snippet.trim().to_string(),
+        }
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} at line {}, column {} in {}",
+            self.message, self.line, self.column, self.construct
+        )?;
+
+        if !self.snippet.is_empty() {
+            write!(f, ": `{}`", self.snippet)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Nodes read from a SVG document along with the errors recovered from
+#[derive(Debug, Default)]
+pub struct Parsed {
+    pub nodes: Vec<Node>,
+    pub warnings: Vec<ParseError>,
+}
+
+/// Reads SVG documents into a tree of [`Node`]s.
+///
+/// Malformed documents are rejected with a [`ParseError`] unless recovery
+/// mode is enabled, in which case the nodes read before the error are kept
+/// and the error is reported as a warning.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Parser {
+    recover: bool,
+    spans: bool,
+    lossless: bool,
+}
 
 impl Parser {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Enables or disables best-effort recovery from malformed documents
+    pub fn recover(mut self, recover: bool) -> Self {
+        self.recover = recover;
+        self
+    }
+
+    /// Enables or disables recording the location of nodes and attributes
+    /// in the source document, refer to [`Element::spans`] and
+    /// [`NodeRef::span`].
+    ///
+    /// [`NodeRef::span`]: super::node::NodeRef::span
+    pub fn spans(mut self, spans: bool) -> Self {
+        self.spans = spans;
+        self
+    }
+
+    /// Enables or disables recording the source text of nodes, so nodes left
+    /// untouched are written back exactly as they were read.
+    pub fn lossless(mut self, lossless: bool) -> Self {
+        self.lossless = lossless;
+        self
+    }
+
+    /// Reads a SVG document, failing with a [`ParseError`] if it is
+    /// malformed.
     pub fn read<R: Read>(r: R) -> Result<Vec<Node>> {
+        Ok(Self::new().parse(r)?.nodes)
+    }
+
+    /// Reads a SVG document with the options of this parser
+    pub fn parse<R: Read>(&self, mut r: R) -> Result<Parsed> {
+        let mut source = Vec::new();
+        r.read_to_end(&mut source)?;
+
+        // The reader reports a start of document with default values even
+        // when there is no XML declaration, so it is looked up in the source.
+        let declaration = Self::declaration(&source);
+        let text = Self::text(&source, declaration.as_deref());
+        // Also used to find the order of the attributes in start tags
+        let source_map = text.map(SourceMap::new);
+        let mut raw_reader = match (self.lossless || self.spans, text) {
+            (true, Some(text)) => Some(RawReader::new(text)),
+            (true, None) => {
+                tracing::warn!(
+                    "Source is not encoded with UTF-8, disabling lossless mode and spans"
+                );
+                None
+            }
+            _ => None,
+        };
+        let mut siblings = Siblings::default();
         let mut parser = ParserConfig::new()
             .ignore_comments(false)
             .ignore_end_of_stream(false)
             .ignore_root_level_whitespace(false)
             .ignore_invalid_encoding_declarations(false)
-            .create_reader(BufReader::new(r));
+            .create_reader(source.as_slice());
         let mut els = Vec::new();
+        // Elements which have been opened but not yet closed, the last one
+        // being the innermost.
+        let mut open: Vec<Element> = Vec::new();
+        // Namespaces in scope of each open element
+        let mut scopes: Vec<Namespace> = Vec::new();
+        // Whether each open element is written with a self-closing tag
+        let mut self_closing: Vec<bool> = Vec::new();
         let mut is_doctype_grabbed = false;
+        let mut warnings = Vec::new();
+
+        loop {
+            let ev = match parser.next() {
+                Ok(ev) => ev,
+                Err(err) => {
+                    let error = ParseError::new(&err, &source, &open);
+
+                    if !self.recover {
+                        return Err(error.into());
+                    }
+
+                    warnings.push(error);
+                    break;
+                }
+            };
+
+            let kind = match &ev {
+                XmlEvent::StartDocument { .. } if declaration.is_none() => None,
+                XmlEvent::StartDocument { .. } => Some(TokenKind::Declaration),
+                XmlEvent::StartElement { .. } => Some(TokenKind::StartTag),
+                XmlEvent::EndElement { .. } => match self_closing.last() {
+                    Some(true) => None,
+                    _ => Some(TokenKind::EndTag),
+                },
+                XmlEvent::ProcessingInstruction { .. } => Some(TokenKind::ProcessingInstruction),
+                XmlEvent::Comment(_) => Some(TokenKind::Comment),
+                XmlEvent::CData(_) => Some(TokenKind::CData),
+                XmlEvent::Whitespace(_) | XmlEvent::Characters(_) => Some(TokenKind::Text),
+                XmlEvent::EndDocument => None,
+            };
+            let token = match (kind, raw_reader.as_mut()) {
+                (Some(kind), Some(reader)) => {
+                    let token = reader.next(kind);
+
+                    if token.is_none() {
+                        tracing::warn!("Source text not found, disabling lossless mode and spans");
+                        raw_reader = None;
+                    }
+
+                    token
+                }
+                _ => None,
+            };
+            let span = match (self.spans, &source_map, token) {
+                (true, Some(source_map), Some((start, text))) => {
+                    Some(source_map.span(start, start + text.len()))
+                }
+                _ => None,
+            };
+            let raw = token.filter(|_| self.lossless).map(|(_, text)| text);
+
+            if let Some((start, doctype)) =
+                raw_reader.as_mut().and_then(|reader| reader.doctype.take())
+            {
+                let node = Node::Doctype(parser.doctype().unwrap_or(doctype).to_string());
+
+                if self.lossless {
+                    siblings.raw.push((node.clone(), doctype.to_string()));
+                }
+
+                if let (true, Some(source_map)) = (self.spans, &source_map) {
+                    let span = source_map.span(start, start + doctype.len());
+                    siblings.spans.push((node.clone(), span));
+                }
+
+                els.push(node);
+                is_doctype_grabbed = true;
+            }
 
-        while let Ok(ev) = parser.next() {
             match ev {
                 XmlEvent::StartDocument {
-                    version, encoding, ..
+                    version,
+                    encoding,
+                    standalone,
                 } => {
+                    let Some(declaration) = &declaration else {
+                        continue;
+                    };
+
                     let node = Node::Declaration {
                         version: version.into(),
-                        encoding,
+                        encoding: declaration.contains("encoding").then_some(encoding),
+                        standalone,
                     };
 
-                    els.push(node);
+                    Self::append_leaf(&mut els, &mut open, &mut siblings, node, raw, span);
                 }
                 XmlEvent::StartElement {
-                    name, attributes, ..
+                    name,
+                    attributes,
+                    namespace,
                 } => {
-                    let attributes: Vec<Attribute> =
-                        attributes.into_iter().map(Attribute::from).collect();
+                    let spans = source_map.as_ref().and_then(|source_map| {
+                        let position = parser.position();
+                        let offset = source_map.offset(position.row, position.column);
+                        source_map.start_tag(offset)
+                    });
 
-                    let element = Element {
-                        r#type: ElementType::Open,
-                        name: name.local_name,
-                        attributes,
+                    let attributes = match &spans {
+                        Some(spans) => Self::attributes_in_order(spans, attributes, &namespace),
+                        None => {
+                            let mut declarations = Self::declarations(&namespace, scopes.last());
+                            declarations.extend(attributes.into_iter().map(Attribute::from));
+                            declarations
+                        }
                     };
 
+                    let mut element = Element::new(name.local_name);
+                    element.prefix = name.prefix;
+                    element.namespace = name.namespace;
+                    element.attributes = attributes;
+
+                    if self.spans {
+                        element.spans = spans.map(Box::new);
+                    }
+
+                    if let Some(start_tag) = raw {
+                        element.raw = Some(Box::new(RawElement::new(&element, start_tag)));
+                    }
+
                     if let Some(doctype) = parser.doctype() {
                         if !is_doctype_grabbed {
                             els.push(Node::Doctype(doctype.to_string()));
@@ -52,39 +298,291 @@ impl Parser {
                         }
                     }
 
-                    els.push(Node::Element(element));
+                    self_closing.push(token.is_some_and(|(_, tag)| tag.ends_with("/>")));
+                    open.push(element);
+                    scopes.push(namespace);
                 }
-                XmlEvent::EndElement { name, .. } => {
-                    let element = Element {
-                        r#type: ElementType::Close,
-                        name: name.local_name,
-                        attributes: Vec::new(),
-                    };
+                XmlEvent::EndElement { .. } => {
+                    scopes.pop();
+                    self_closing.pop();
 
-                    els.push(Node::Element(element));
+                    if let Some(mut element) = open.pop() {
+                        if let (Some(end_tag), Some(element_raw)) = (raw, &mut element.raw) {
+                            element_raw.end_tag = Some(end_tag.to_string());
+                        }
+
+                        if let (Some(source_map), Some(spans)) = (&source_map, &mut element.spans) {
+                            let position = parser.position();
+                            let offset = source_map.offset(position.row, position.column);
+
+                            // Self-closing elements end with their start tag
+                            if source_map.position(offset) != spans.start_tag.start {
+                                spans.element.end = source_map.position(source_map.end_tag(offset));
+                            }
+                        }
+
+                        Self::append(&mut els, &mut open, Node::Element(element));
+                    }
                 }
                 XmlEvent::Comment(value) => {
                     let node = Node::Comment(value);
-                    els.push(node);
+                    Self::append_leaf(&mut els, &mut open, &mut siblings, node, raw, span);
+                }
+                XmlEvent::ProcessingInstruction { name, data } => {
+                    // The reader reports every `/` in the data as `</`, as
+                    // no other `/` is reported the replacement is reversible.
+                    let data = data.map(|data| data.replace("</", "/"));
+                    let node = Node::ProcessingInstruction { name, data };
+                    Self::append_leaf(&mut els, &mut open, &mut siblings, node, raw, span);
                 }
                 XmlEvent::CData(value) => {
                     let node = Node::CData(value);
-                    els.push(node);
+                    Self::append_leaf(&mut els, &mut open, &mut siblings, node, raw, span);
                 }
                 XmlEvent::Whitespace(value) | XmlEvent::Characters(value) => {
                     let node = Node::Characters(value);
-                    els.push(node);
+                    Self::append_leaf(&mut els, &mut open, &mut siblings, node, raw, span);
                 }
                 XmlEvent::EndDocument => {
                     break;
                 }
-                _ => {
-                    tracing::warn!("Ignoring event: {:?}", ev);
+            }
+        }
+
+        // Elements left open when recovering from an error are closed so
+        // their contents are not lost.
+        while let Some(element) = open.pop() {
+            Self::append(&mut els, &mut open, Node::Element(element));
+        }
+
+        // The reader reports no whitespace after the root element, which is
+        // kept to write the document back as read.
+        if let Some((text, rest)) =
+            raw_reader.map(|reader| (reader.lexer.text(), reader.lexer.rest()))
+        {
+            if !rest.is_empty() && rest.trim().is_empty() {
+                let node = Node::Characters(rest.to_string());
+
+                if self.lossless {
+                    siblings.raw.push((node.clone(), rest.to_string()));
+                }
+
+                if let (true, Some(source_map)) = (self.spans, &source_map) {
+                    let span = source_map.span(text.len() - rest.len(), text.len());
+                    siblings.spans.push((node.clone(), span));
                 }
+
+                els.push(node);
             }
         }
 
-        Ok(els)
+        if let Some(root) = els.iter_mut().find_map(Node::as_element_mut) {
+            if let Some(raw) = &mut root.raw {
+                raw.siblings = siblings.raw;
+            }
+
+            if let Some(spans) = &mut root.spans {
+                spans.siblings = siblings.spans;
+            }
+        }
+
+        Ok(Parsed {
+            nodes: els,
+            warnings,
+        })
+    }
+
+    /// Returns the XML declaration the source begins with, if any, decoding
+    /// it from UTF-16 when the source is encoded with it.
+    fn declaration(source: &[u8]) -> Option<String> {
+        let utf16 = |bytes: &[u8], decode: fn([u8; 2]) -> u16| {
+            let units = bytes.chunks_exact(2).map(|pair| decode([pair[0], pair[1]]));
+            char::decode_utf16(units)
+                .map(|ch| ch.unwrap_or(char::REPLACEMENT_CHARACTER))
+                .collect::<String>()
+        };
+        let text = match source {
+            [0xFE, 0xFF, rest @ ..] => utf16(rest, u16::from_be_bytes),
+            [0xFF, 0xFE, rest @ ..] => utf16(rest, u16::from_le_bytes),
+            [0, b'<', 0, b'?', ..] => utf16(source, u16::from_be_bytes),
+            [b'<', 0, b'?', 0, ..] => utf16(source, u16::from_le_bytes),
+            _ => String::from_utf8_lossy(source).into_owned(),
+        };
+        let text = text.strip_prefix('\u{FEFF}').unwrap_or(&text);
+        let rest = text.strip_prefix("<?xml")?;
+
+        if !rest.starts_with(|ch: char| ch.is_ascii_whitespace()) {
+            return None;
+        }
+
+        rest.find("?>").map(|end| rest[..end].to_string())
+    }
+
+    /// Returns the source text when the document is encoded with UTF-8, used
+    /// to locate nodes and find their source text.
+    fn text<'a>(source: &'a [u8], declaration: Option<&str>) -> Option<&'a str> {
+        let encoding = declaration
+            .and_then(|declaration| declaration.split_once("encoding")?.1.split_once('='))
+            .map(|(_, value)| value.trim_start())
+            .and_then(|value| {
+                let quote = value.chars().next()?;
+                value[quote.len_utf8()..].split(quote).next()
+            });
+
+        match encoding {
+            Some(encoding) if !is_utf8(encoding) => None,
+            _ => std::str::from_utf8(source).ok(),
+        }
+    }
+
+    /// Returns the attributes of an element along with its namespace
+    /// declarations, in the order they are written in its start tag.
+    fn attributes_in_order(
+        spans: &ElementSpans,
+        attributes: Vec<OwnedAttribute>,
+        namespace: &Namespace,
+    ) -> Vec<Attribute> {
+        let mut attributes = attributes.into_iter().map(Attribute::from);
+        let mut ordered: Vec<Attribute> = spans
+            .attributes
+            .iter()
+            .filter_map(|(name, _)| {
+                let prefix = match name.split_once(':') {
+                    None if name == NS_XMLNS_PREFIX => NS_NO_PREFIX,
+                    Some((NS_XMLNS_PREFIX, prefix)) => prefix,
+                    _ => return attributes.next(),
+                };
+
+                Some(Attribute::Declaration {
+                    key: name.clone(),
+                    value: namespace.get(prefix).unwrap_or_default().to_string(),
+                })
+            })
+            .collect();
+
+        // Attributes not written in the start tag, such as the ones with a
+        // default value in the document type.
+        ordered.extend(attributes);
+        ordered
+    }
+
+    /// Returns the namespace declarations of an element, which are the
+    /// mappings in its scope that differ from the ones of its parent.
+    ///
+    /// Only used when the start tag is not found in the source, in which
+    /// case redeclarations are dropped and the order is lost.
+    fn declarations(namespace: &Namespace, parent: Option<&Namespace>) -> Vec<Attribute> {
+        namespace
+            .iter()
+            .filter(|(prefix, _)| *prefix != NS_XML_PREFIX && *prefix != NS_XMLNS_PREFIX)
+            .filter(|(prefix, uri)| {
+                let inherited = match parent.and_then(|parent| parent.get(prefix)) {
+                    Some(inherited) => inherited,
+                    // Elements without a default namespace are in no namespace
+                    None if *prefix == NS_NO_PREFIX => "",
+                    None => return true,
+                };
+
+                inherited != *uri
+            })
+            .map(|(prefix, uri)| Attribute::Declaration {
+                key: match prefix {
+                    NS_NO_PREFIX => NS_XMLNS_PREFIX.to_string(),
+                    prefix => format!("{}:{}", NS_XMLNS_PREFIX, prefix),
+                },
+                value: uri.to_string(),
+            })
+            .collect()
+    }
+
+    /// Appends a node which is not an element along with its source text
+    /// and location, refer to [`Parser::append`].
+    fn append_leaf(
+        els: &mut Vec<Node>,
+        open: &mut [Element],
+        siblings: &mut Siblings,
+        node: Node,
+        raw: Option<&str>,
+        span: Option<Span>,
+    ) {
+        if let Some(raw) = raw {
+            let raw_nodes = match open.last_mut() {
+                Some(parent) => parent.raw.as_mut().map(|parent| &mut parent.children),
+                None => Some(&mut siblings.raw),
+            };
+
+            if let Some(raw_nodes) = raw_nodes {
+                raw_nodes.push((node.clone(), raw.to_string()));
+            }
+        }
+
+        if let Some(span) = span {
+            let node_spans = match open.last_mut() {
+                Some(parent) => parent.spans.as_mut().map(|parent| &mut parent.children),
+                None => Some(&mut siblings.spans),
+            };
+
+            if let Some(node_spans) = node_spans {
+                node_spans.push((node.clone(), span));
+            }
+        }
+
+        Self::append(els, open, node);
+    }
+
+    /// Appends a node to the innermost open element, or to the top-level
+    /// nodes when no element is open.
+    fn append(els: &mut Vec<Node>, open: &mut [Element], node: Node) {
+        match open.last_mut() {
+            Some(parent) => parent.children.push(node),
+            None => els.push(node),
+        }
+    }
+}
+
+/// Returns `true` if `encoding` names UTF-8, which documents are written
+/// with.
+pub(crate) fn is_utf8(encoding: &str) -> bool {
+    encoding.eq_ignore_ascii_case("utf-8") || encoding.eq_ignore_ascii_case("utf8")
+}
+
+/// Source text and locations of the top-level nodes which are not
+/// elements, kept on the root element.
+#[derive(Default)]
+struct Siblings {
+    raw: RawNodes,
+    spans: NodeSpans,
+}
+
+/// Finds the source text of the events produced by the reader
+struct RawReader<'a> {
+    lexer: Lexer<'a>,
+    /// `DOCTYPE` declaration found while looking for the source text of an
+    /// event, as the reader produces no event for it, along with its offset.
+    doctype: Option<(usize, &'a str)>,
+}
+
+impl<'a> RawReader<'a> {
+    fn new(text: &'a str) -> Self {
+        Self {
+            lexer: Lexer::new(text),
+            doctype: None,
+        }
+    }
+
+    /// Returns the offset and source text of the next event, or `None` if
+    /// the source does not match the event.
+    fn next(&mut self, kind: TokenKind) -> Option<(usize, &'a str)> {
+        loop {
+            let (found, text) = self.lexer.next_token()?;
+            let start = self.lexer.position() - text.len();
+
+            match found {
+                found if found == kind => return Some((start, text)),
+                TokenKind::Doctype => self.doctype = Some((start, text)),
+                _ => return None,
+            }
+        }
     }
 }
 
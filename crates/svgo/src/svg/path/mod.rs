@@ -0,0 +1,470 @@
+//! Path data for the `d` attribute of `<path>` elements
+//! Read more: https://www.w3.org/TR/SVG11/paths.html#PathData
+
+pub mod parser;
+
+use std::fmt::Display;
+use std::str::FromStr;
+
+pub use self::parser::{PathError, PathErrorKind};
+
+use super::transform::Matrix;
+
+/// Drawing command in a path data, with its arguments
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Command {
+    /// `M`: Starts a new sub-path at the given point
+    MoveTo { x: f64, y: f64 },
+    /// `L`: Draws a straight line to the given point
+    LineTo { x: f64, y: f64 },
+    /// `H`: Draws a horizontal line to the given `x` coordinate
+    HorizontalLineTo { x: f64 },
+    /// `V`: Draws a vertical line to the given `y` coordinate
+    VerticalLineTo { y: f64 },
+    /// `C`: Draws a cubic Bézier curve using two control points
+    CurveTo {
+        x1: f64,
+        y1: f64,
+        x2: f64,
+        y2: f64,
+        x: f64,
+        y: f64,
+    },
+    /// `S`: Draws a cubic Bézier curve whose first control point is the
+    /// reflection of the previous curve's second control point
+    SmoothCurveTo { x2: f64, y2: f64, x: f64, y: f64 },
+    /// `Q`: Draws a quadratic Bézier curve using a control point
+    QuadraticCurveTo { x1: f64, y1: f64, x: f64, y: f64 },
+    /// `T`: Draws a quadratic Bézier curve whose control point is the
+    /// reflection of the previous curve's control point
+    SmoothQuadraticCurveTo { x: f64, y: f64 },
+    /// `A`: Draws an elliptical arc to the given point
+    ArcTo {
+        rx: f64,
+        ry: f64,
+        x_axis_rotation: f64,
+        large_arc: bool,
+        sweep: bool,
+        x: f64,
+        y: f64,
+    },
+    /// `Z`: Closes the current sub-path
+    ClosePath,
+}
+
+impl Command {
+    /// Returns the uppercase letter identifying this command
+    pub fn letter(&self) -> char {
+        match self {
+            Self::MoveTo { .. } => 'M',
+            Self::LineTo { .. } => 'L',
+            Self::HorizontalLineTo { .. } => 'H',
+            Self::VerticalLineTo { .. } => 'V',
+            Self::CurveTo { .. } => 'C',
+            Self::SmoothCurveTo { .. } => 'S',
+            Self::QuadraticCurveTo { .. } => 'Q',
+            Self::SmoothQuadraticCurveTo { .. } => 'T',
+            Self::ArcTo { .. } => 'A',
+            Self::ClosePath => 'Z',
+        }
+    }
+
+    /// Returns the point this command ends at, or `None` for
+    /// [`Command::ClosePath`] which ends at the start of the sub-path and
+    /// for [`Command::HorizontalLineTo`] and [`Command::VerticalLineTo`]
+    /// which only hold one coordinate.
+    pub fn end_point(&self) -> Option<(f64, f64)> {
+        match *self {
+            Self::MoveTo { x, y }
+            | Self::LineTo { x, y }
+            | Self::CurveTo { x, y, .. }
+            | Self::SmoothCurveTo { x, y, .. }
+            | Self::QuadraticCurveTo { x, y, .. }
+            | Self::SmoothQuadraticCurveTo { x, y }
+            | Self::ArcTo { x, y, .. } => Some((x, y)),
+            Self::HorizontalLineTo { .. } | Self::VerticalLineTo { .. } | Self::ClosePath => None,
+        }
+    }
+
+    /// Returns the arguments of this command in the order they are written,
+    /// arc flags are represented as `0` or `1`.
+    pub fn args(&self) -> Vec<f64> {
+        match *self {
+            Self::MoveTo { x, y }
+            | Self::LineTo { x, y }
+            | Self::SmoothQuadraticCurveTo { x, y } => vec![x, y],
+            Self::HorizontalLineTo { x } => vec![x],
+            Self::VerticalLineTo { y } => vec![y],
+            Self::CurveTo {
+                x1,
+                y1,
+                x2,
+                y2,
+                x,
+                y,
+            } => vec![x1, y1, x2, y2, x, y],
+            Self::SmoothCurveTo { x2, y2, x, y } => vec![x2, y2, x, y],
+            Self::QuadraticCurveTo { x1, y1, x, y } => vec![x1, y1, x, y],
+            Self::ArcTo {
+                rx,
+                ry,
+                x_axis_rotation,
+                large_arc,
+                sweep,
+                x,
+                y,
+            } => vec![
+                rx,
+                ry,
+                x_axis_rotation,
+                f64::from(u8::from(large_arc)),
+                f64::from(u8::from(sweep)),
+                x,
+                y,
+            ],
+            Self::ClosePath => Vec::new(),
+        }
+    }
+}
+
+/// A [`Command`] along with whether its coordinates are absolute or relative
+/// to the current point.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Segment {
+    pub absolute: bool,
+    pub command: Command,
+}
+
+impl Segment {
+    pub fn new(absolute: bool, command: Command) -> Self {
+        Self { absolute, command }
+    }
+
+    /// Returns the letter for this segment, uppercase for absolute and
+    /// lowercase for relative coordinates.
+    pub fn letter(&self) -> char {
+        let letter = self.command.letter();
+
+        if self.absolute {
+            letter
+        } else {
+            letter.to_ascii_lowercase()
+        }
+    }
+}
+
+impl Display for Segment {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.letter())?;
+
+        for (index, arg) in self.command.args().iter().enumerate() {
+            if index > 0 {
+                write!(f, " ")?;
+            }
+
+            write!(f, "{}", arg)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Parsed contents of a `d` attribute
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PathData(pub Vec<Segment>);
+
+impl PathData {
+    /// Parses path data from the value of a `d` attribute
+    pub fn parse(value: &str) -> Result<Self, PathError> {
+        parser::parse(value).map(Self)
+    }
+
+    /// Returns the segments in this path data
+    #[inline]
+    pub fn segments(&self) -> &[Segment] {
+        &self.0
+    }
+
+    /// Returns an equivalent path data where every segment uses absolute
+    /// coordinates and shorthand commands are expanded into their full
+    /// forms.
+    ///
+    /// The resulting segments only hold `M`, `L`, `C`, `Q`, `A` and `Z`
+    /// commands.
+    pub fn to_absolute(&self) -> PathData {
+        let mut segments = Vec::with_capacity(self.0.len());
+        // Current point and start of the current sub-path
+        let (mut cx, mut cy) = (0.0, 0.0);
+        let (mut sx, mut sy) = (0.0, 0.0);
+        // Control points of the previous segment, used to expand shorthands
+        let mut cubic_control: Option<(f64, f64)> = None;
+        let mut quadratic_control: Option<(f64, f64)> = None;
+
+        for segment in &self.0 {
+            let (ox, oy) = if segment.absolute {
+                (0.0, 0.0)
+            } else {
+                (cx, cy)
+            };
+            let reflect = |control: Option<(f64, f64)>| match control {
+                Some((x, y)) => (2.0 * cx - x, 2.0 * cy - y),
+                None => (cx, cy),
+            };
+
+            let command = match segment.command {
+                Command::MoveTo { x, y } => {
+                    (sx, sy) = (ox + x, oy + y);
+                    Command::MoveTo { x: sx, y: sy }
+                }
+                Command::LineTo { x, y } => Command::LineTo {
+                    x: ox + x,
+                    y: oy + y,
+                },
+                Command::HorizontalLineTo { x } => Command::LineTo { x: ox + x, y: cy },
+                Command::VerticalLineTo { y } => Command::LineTo { x: cx, y: oy + y },
+                Command::CurveTo {
+                    x1,
+                    y1,
+                    x2,
+                    y2,
+                    x,
+                    y,
+                } => Command::CurveTo {
+                    x1: ox + x1,
+                    y1: oy + y1,
+                    x2: ox + x2,
+                    y2: oy + y2,
+                    x: ox + x,
+                    y: oy + y,
+                },
+                Command::SmoothCurveTo { x2, y2, x, y } => {
+                    let (x1, y1) = reflect(cubic_control);
+
+                    Command::CurveTo {
+                        x1,
+                        y1,
+                        x2: ox + x2,
+                        y2: oy + y2,
+                        x: ox + x,
+                        y: oy + y,
+                    }
+                }
+                Command::QuadraticCurveTo { x1, y1, x, y } => Command::QuadraticCurveTo {
+                    x1: ox + x1,
+                    y1: oy + y1,
+                    x: ox + x,
+                    y: oy + y,
+                },
+                Command::SmoothQuadraticCurveTo { x, y } => {
+                    let (x1, y1) = reflect(quadratic_control);
+
+                    Command::QuadraticCurveTo {
+                        x1,
+                        y1,
+                        x: ox + x,
+                        y: oy + y,
+                    }
+                }
+                Command::ArcTo {
+                    rx,
+                    ry,
+                    x_axis_rotation,
+                    large_arc,
+                    sweep,
+                    x,
+                    y,
+                } => Command::ArcTo {
+                    rx,
+                    ry,
+                    x_axis_rotation,
+                    large_arc,
+                    sweep,
+                    x: ox + x,
+                    y: oy + y,
+                },
+                Command::ClosePath => Command::ClosePath,
+            };
+
+            (cubic_control, quadratic_control) = match command {
+                Command::CurveTo { x2, y2, .. } => (Some((x2, y2)), None),
+                Command::QuadraticCurveTo { x1, y1, .. } => (None, Some((x1, y1))),
+                _ => (None, None),
+            };
+
+            (cx, cy) = command.end_point().unwrap_or((sx, sy));
+            segments.push(Segment::new(true, command));
+        }
+
+        PathData(segments)
+    }
+
+    /// Returns an equivalent path data in absolute coordinates, as returned
+    /// by [`PathData::to_absolute`], with `matrix` applied to every point.
+    pub fn transform(&self, matrix: &Matrix) -> PathData {
+        let mut segments = self.to_absolute().0;
+        // Current point and start of the current sub-path before applying
+        // the matrix, used to transform arcs.
+        let mut current = (0.0, 0.0);
+        let mut start = (0.0, 0.0);
+
+        for segment in segments.iter_mut() {
+            let end = segment.command.end_point().unwrap_or(start);
+
+            segment.command = match segment.command {
+                Command::MoveTo { x, y } => {
+                    start = (x, y);
+                    let (x, y) = matrix.apply(x, y);
+                    Command::MoveTo { x, y }
+                }
+                Command::LineTo { x, y } => {
+                    let (x, y) = matrix.apply(x, y);
+                    Command::LineTo { x, y }
+                }
+                Command::CurveTo {
+                    x1,
+                    y1,
+                    x2,
+                    y2,
+                    x,
+                    y,
+                } => {
+                    let (x1, y1) = matrix.apply(x1, y1);
+                    let (x2, y2) = matrix.apply(x2, y2);
+                    let (x, y) = matrix.apply(x, y);
+                    Command::CurveTo {
+                        x1,
+                        y1,
+                        x2,
+                        y2,
+                        x,
+                        y,
+                    }
+                }
+                Command::QuadraticCurveTo { x1, y1, x, y } => {
+                    let (x1, y1) = matrix.apply(x1, y1);
+                    let (x, y) = matrix.apply(x, y);
+                    Command::QuadraticCurveTo { x1, y1, x, y }
+                }
+                Command::ArcTo {
+                    rx,
+                    ry,
+                    x_axis_rotation,
+                    large_arc,
+                    sweep,
+                    x,
+                    y,
+                } => {
+                    let (rx, ry, x_axis_rotation) =
+                        transform_arc(matrix, current, (x, y), rx, ry, x_axis_rotation);
+                    let (x, y) = matrix.apply(x, y);
+                    Command::ArcTo {
+                        rx,
+                        ry,
+                        x_axis_rotation,
+                        large_arc,
+                        // Mirroring reverses the direction of the arc
+                        sweep: sweep != (matrix.determinant() < 0.0),
+                        x,
+                        y,
+                    }
+                }
+                command => command,
+            };
+
+            current = end;
+        }
+
+        PathData(segments)
+    }
+}
+
+/// Returns the radii and rotation of an arc from `from` to `to` once
+/// `matrix` is applied to it.
+///
+/// The arc's ellipse is represented as a matrix mapping the unit circle onto
+/// it, which is then multiplied by `matrix` and decomposed back into radii
+/// and rotation.
+fn transform_arc(
+    matrix: &Matrix,
+    from: (f64, f64),
+    to: (f64, f64),
+    rx: f64,
+    ry: f64,
+    x_axis_rotation: f64,
+) -> (f64, f64, f64) {
+    let (dx, dy) = (to.0 - from.0, to.1 - from.1);
+    let (mut rx, mut ry) = (rx.abs(), ry.abs());
+    let (sin, cos) = x_axis_rotation.to_radians().sin_cos();
+
+    // Radii too small to reach the end point are scaled up, as renderers do
+    if rx > 0.0 && ry > 0.0 {
+        let lambda = (dx * cos + dy * sin).powi(2) / (4.0 * rx * rx)
+            + (dy * cos - dx * sin).powi(2) / (4.0 * ry * ry);
+
+        if lambda > 1.0 {
+            rx *= lambda.sqrt();
+            ry *= lambda.sqrt();
+        }
+    }
+
+    let ellipse = Matrix::new(rx * cos, rx * sin, -ry * sin, ry * cos, 0.0, 0.0);
+    let m = matrix.multiply(&ellipse);
+
+    let last_column = m.c * m.c + m.d * m.d;
+    let square_sum = m.a * m.a + m.b * m.b + last_column;
+    let root = (m.a - m.d).hypot(m.b + m.c) * (m.a + m.d).hypot(m.b - m.c);
+
+    if root == 0.0 {
+        // The transformed ellipse is a circle
+        let radius = (square_sum / 2.0).sqrt();
+        return (radius, radius, 0.0);
+    }
+
+    let major_square = (square_sum + root) / 2.0;
+    let minor_square = (square_sum - root) / 2.0;
+    let is_major = (major_square - last_column).abs() > 1e-6;
+    let sub = if is_major { major_square } else { minor_square } - last_column;
+    let rows_sum = m.a * m.c + m.b * m.d;
+    let term1 = m.a * sub + m.c * rows_sum;
+    let term2 = m.b * sub + m.d * rows_sum;
+    let length = term1.hypot(term2);
+
+    let rotation = if length == 0.0 {
+        0.0
+    } else {
+        let sign = if (is_major && term2 < 0.0) || (!is_major && term1 > 0.0) {
+            -1.0
+        } else {
+            1.0
+        };
+
+        sign * (if is_major { term1 } else { term2 } / length)
+            .clamp(-1.0, 1.0)
+            .acos()
+            .to_degrees()
+    };
+
+    (major_square.sqrt(), minor_square.max(0.0).sqrt(), rotation)
+}
+
+impl FromStr for PathData {
+    type Err = PathError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl Display for PathData {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (index, segment) in self.0.iter().enumerate() {
+            if index > 0 {
+                write!(f, " ")?;
+            }
+
+            write!(f, "{}", segment)?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,353 @@
+//! Parser for path data following the SVG 1.1 grammar
+//! Read more: https://www.w3.org/TR/SVG11/paths.html#PathDataBNF
+
+use std::fmt::Display;
+
+use super::{Command, Segment};
+
+/// Reason for path data to be invalid
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PathErrorKind {
+    /// Path data must begin with a `M` or `m` command
+    MissingMoveTo,
+    /// A character which is not valid at the given position was found
+    UnexpectedCharacter(char),
+    /// Path data ended in the middle of a command
+    UnexpectedEnd,
+    /// A number was expected at the given position
+    InvalidNumber,
+    /// An arc flag other than `0` or `1` was found
+    InvalidFlag,
+}
+
+impl Display for PathErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MissingMoveTo => write!(f, "path data must begin with a move to command"),
+            Self::UnexpectedCharacter(ch) => write!(f, "unexpected character '{}'", ch),
+            Self::UnexpectedEnd => write!(f, "unexpected end of path data"),
+            Self::InvalidNumber => write!(f, "expected a number"),
+            Self::InvalidFlag => write!(f, "expected an arc flag"),
+        }
+    }
+}
+
+/// Error found when parsing path data
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PathError {
+    /// Byte offset in the path data where the error was found
+    pub position: usize,
+    pub kind: PathErrorKind,
+}
+
+impl Display for PathError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} at position {}", self.kind, self.position)
+    }
+}
+
+impl std::error::Error for PathError {}
+
+/// Parses the value of a `d` attribute into its segments
+pub fn parse(value: &str) -> Result<Vec<Segment>, PathError> {
+    let mut cursor = Cursor {
+        bytes: value.as_bytes(),
+        position: 0,
+    };
+    let mut segments: Vec<Segment> = Vec::new();
+
+    loop {
+        cursor.skip_whitespace();
+
+        let Some(byte) = cursor.peek() else {
+            break;
+        };
+
+        let letter = byte.to_ascii_uppercase();
+        let absolute = byte.is_ascii_uppercase();
+
+        if !b"MLHVCSQTAZ".contains(&letter) {
+            return Err(cursor.unexpected());
+        }
+
+        if segments.is_empty() && letter != b'M' {
+            return Err(cursor.error(PathErrorKind::MissingMoveTo));
+        }
+
+        cursor.position += 1;
+
+        if letter == b'Z' {
+            segments.push(Segment::new(absolute, Command::ClosePath));
+            continue;
+        }
+
+        let mut command = cursor.command(letter)?;
+
+        loop {
+            segments.push(Segment::new(absolute, command));
+            cursor.skip_comma_whitespace();
+
+            if !cursor.starts_number() {
+                break;
+            }
+
+            // Coordinates following a move to are treated as implicit line to
+            // commands.
+            let letter = if letter == b'M' { b'L' } else { letter };
+            command = cursor.command(letter)?;
+        }
+    }
+
+    Ok(segments)
+}
+
+struct Cursor<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl Cursor<'_> {
+    fn command(&mut self, letter: u8) -> Result<Command, PathError> {
+        let command = match letter {
+            b'M' => Command::MoveTo {
+                x: self.first_number()?,
+                y: self.number()?,
+            },
+            b'L' => Command::LineTo {
+                x: self.first_number()?,
+                y: self.number()?,
+            },
+            b'H' => Command::HorizontalLineTo {
+                x: self.first_number()?,
+            },
+            b'V' => Command::VerticalLineTo {
+                y: self.first_number()?,
+            },
+            b'C' => Command::CurveTo {
+                x1: self.first_number()?,
+                y1: self.number()?,
+                x2: self.number()?,
+                y2: self.number()?,
+                x: self.number()?,
+                y: self.number()?,
+            },
+            b'S' => Command::SmoothCurveTo {
+                x2: self.first_number()?,
+                y2: self.number()?,
+                x: self.number()?,
+                y: self.number()?,
+            },
+            b'Q' => Command::QuadraticCurveTo {
+                x1: self.first_number()?,
+                y1: self.number()?,
+                x: self.number()?,
+                y: self.number()?,
+            },
+            b'T' => Command::SmoothQuadraticCurveTo {
+                x: self.first_number()?,
+                y: self.number()?,
+            },
+            b'A' => Command::ArcTo {
+                rx: self.first_number()?,
+                ry: self.number()?,
+                x_axis_rotation: self.number()?,
+                large_arc: self.flag()?,
+                sweep: self.flag()?,
+                x: self.number()?,
+                y: self.number()?,
+            },
+            _ => unreachable!("Path command letters are validated before parsing arguments"),
+        };
+
+        Ok(command)
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.position).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(b' ' | b'\t' | b'\n' | b'\r' | b'\x0C')) {
+            self.position += 1;
+        }
+    }
+
+    fn skip_comma_whitespace(&mut self) {
+        self.skip_whitespace();
+
+        if self.peek() == Some(b',') {
+            self.position += 1;
+            self.skip_whitespace();
+        }
+    }
+
+    fn starts_number(&self) -> bool {
+        matches!(self.peek(), Some(b'0'..=b'9' | b'-' | b'+' | b'.'))
+    }
+
+    /// Reads the first argument of a command, which can only be preceded by
+    /// whitespace.
+    fn first_number(&mut self) -> Result<f64, PathError> {
+        self.skip_whitespace();
+        self.read_number()
+    }
+
+    fn number(&mut self) -> Result<f64, PathError> {
+        self.skip_comma_whitespace();
+        self.read_number()
+    }
+
+    fn read_number(&mut self) -> Result<f64, PathError> {
+        let start = self.position;
+
+        if matches!(self.peek(), Some(b'-' | b'+')) {
+            self.position += 1;
+        }
+
+        let integer_digits = self.skip_digits();
+        let mut fraction_digits = 0;
+
+        if self.peek() == Some(b'.') {
+            self.position += 1;
+            fraction_digits = self.skip_digits();
+        }
+
+        if integer_digits == 0 && fraction_digits == 0 {
+            return Err(match self.peek() {
+                Some(_) => self.error(PathErrorKind::InvalidNumber),
+                None => self.error(PathErrorKind::UnexpectedEnd),
+            });
+        }
+
+        if matches!(self.peek(), Some(b'e' | b'E')) {
+            self.position += 1;
+
+            if matches!(self.peek(), Some(b'-' | b'+')) {
+                self.position += 1;
+            }
+
+            if self.skip_digits() == 0 {
+                return Err(self.error(PathErrorKind::InvalidNumber));
+            }
+        }
+
+        // The slice only holds the ASCII characters validated above
+        std::str::from_utf8(&self.bytes[start..self.position])
+            .ok()
+            .and_then(|text| text.parse().ok())
+            .ok_or(PathError {
+                position: start,
+                kind: PathErrorKind::InvalidNumber,
+            })
+    }
+
+    fn flag(&mut self) -> Result<bool, PathError> {
+        self.skip_comma_whitespace();
+
+        match self.peek() {
+            Some(b'0') => {
+                self.position += 1;
+                Ok(false)
+            }
+            Some(b'1') => {
+                self.position += 1;
+                Ok(true)
+            }
+            Some(_) => Err(self.error(PathErrorKind::InvalidFlag)),
+            None => Err(self.error(PathErrorKind::UnexpectedEnd)),
+        }
+    }
+
+    fn skip_digits(&mut self) -> usize {
+        let start = self.position;
+
+        while matches!(self.peek(), Some(b'0'..=b'9')) {
+            self.position += 1;
+        }
+
+        self.position - start
+    }
+
+    fn unexpected(&self) -> PathError {
+        // Report the whole character rather than a single byte of it
+        let ch = std::str::from_utf8(&self.bytes[self.position..])
+            .ok()
+            .and_then(|rest| rest.chars().next())
+            .unwrap_or(char::REPLACEMENT_CHARACTER);
+
+        self.error(PathErrorKind::UnexpectedCharacter(ch))
+    }
+
+    fn error(&self, kind: PathErrorKind) -> PathError {
+        PathError {
+            position: self.position,
+            kind,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::PathData;
+    use super::*;
+
+    #[test]
+    fn parses_absolute_and_relative_commands() {
+        let path = PathData::parse(
+            "M10,20 l5-5.5.5 0 H1e1 v-.5 c1 2 3 4 5 6 s1 2 3 4 Q1 2 3 4 t1 2 a5 5 30 1 0 10 10 z",
+        )
+        .unwrap();
+        let letters: String = path.segments().iter().map(Segment::letter).collect();
+
+        assert_eq!(letters, "MllHvcsQtaz");
+        assert_eq!(
+            path.segments()[2].command,
+            Command::LineTo { x: 0.5, y: 0.0 }
+        );
+        assert_eq!(
+            path.segments()[3].command,
+            Command::HorizontalLineTo { x: 10.0 }
+        );
+    }
+
+    #[test]
+    fn parses_compact_arc_flags() {
+        let path = PathData::parse("m0 0a5 5 0 1110 10").unwrap();
+
+        assert_eq!(
+            path.segments()[1].command,
+            Command::ArcTo {
+                rx: 5.0,
+                ry: 5.0,
+                x_axis_rotation: 0.0,
+                large_arc: true,
+                sweep: true,
+                x: 10.0,
+                y: 10.0,
+            }
+        );
+    }
+
+    #[test]
+    fn reports_error_positions() {
+        let errors = vec![
+            ("L 10 10", 0, PathErrorKind::MissingMoveTo),
+            ("M 10 10 L 5", 11, PathErrorKind::UnexpectedEnd),
+            ("M 10 10 X 5 5", 8, PathErrorKind::UnexpectedCharacter('X')),
+            ("M 10 10 A 1 1 0 2 0 5 5", 16, PathErrorKind::InvalidFlag),
+            ("M 10 10 L 5 #", 12, PathErrorKind::InvalidNumber),
+        ];
+
+        for (value, position, kind) in errors {
+            assert_eq!(PathData::parse(value), Err(PathError { position, kind }));
+        }
+    }
+
+    #[test]
+    fn serializes_back_to_path_data() {
+        let path = PathData::parse("M 10,20 L 30.5,40 30,10 Z m 1 1").unwrap();
+
+        assert_eq!(path.to_string(), "M10 20 L30.5 40 L30 10 Z m1 1");
+        assert_eq!(PathData::parse(&path.to_string()).unwrap(), path);
+    }
+}
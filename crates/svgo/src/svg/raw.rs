@@ -0,0 +1,201 @@
+//! Source text of nodes read in lossless mode, refer to
+//! [`Parser::lossless`].
+//!
+//! [`Parser::lossless`]: super::parser::Parser::lossless
+
+use super::node::{Element, Node};
+
+/// Nodes without children along with their source text, in the order they
+/// were read.
+pub type RawNodes = Vec<(Node, String)>;
+
+/// Source text of an element, written back as is while the element is left
+/// untouched.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RawElement {
+    /// Element as read, without its children, to detect changes
+    pub(crate) original: Element,
+    pub(crate) start_tag: String,
+    /// `None` for self-closing elements
+    pub(crate) end_tag: Option<String>,
+    /// Source text of the children which are not elements
+    pub(crate) children: RawNodes,
+    /// Source text of the top-level nodes which are not elements, only kept
+    /// on the root element.
+    pub(crate) siblings: RawNodes,
+}
+
+impl RawElement {
+    pub(crate) fn new(element: &Element, start_tag: &str) -> Self {
+        let mut original = Element::new(element.name.clone());
+        original.prefix = element.prefix.clone();
+        original.namespace = element.namespace.clone();
+        original.attributes = element.attributes.clone();
+
+        Self {
+            original,
+            start_tag: start_tag.to_string(),
+            end_tag: None,
+            children: Vec::new(),
+            siblings: Vec::new(),
+        }
+    }
+
+    pub(crate) fn is_self_closing(&self) -> bool {
+        self.start_tag.ends_with("/>")
+    }
+
+    /// Determines whether the start tag of `element` is unchanged since it
+    /// was read.
+    pub(crate) fn matches(&self, element: &Element) -> bool {
+        self.original.name == element.name
+            && self.original.prefix == element.prefix
+            && self.original.namespace == element.namespace
+            && self.original.attributes == element.attributes
+    }
+}
+
+/// Kind of markup found in the source of a XML document
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum TokenKind {
+    Declaration,
+    ProcessingInstruction,
+    Comment,
+    CData,
+    Doctype,
+    StartTag,
+    EndTag,
+    Text,
+}
+
+/// Splits the source of a XML document into markup, used to find the source
+/// text of the events produced by the reader.
+pub(crate) struct Lexer<'a> {
+    text: &'a str,
+    position: usize,
+}
+
+impl<'a> Lexer<'a> {
+    pub(crate) fn new(text: &'a str) -> Self {
+        Self { text, position: 0 }
+    }
+
+    pub(crate) fn next_token(&mut self) -> Option<(TokenKind, &'a str)> {
+        let rest = &self.text[self.position..];
+        // A byte order mark is kept along with the XML declaration
+        let markup = rest.strip_prefix('\u{FEFF}').unwrap_or(rest);
+
+        let (kind, length) = if rest.is_empty() {
+            return None;
+        } else if self.position == 0
+            && markup.starts_with("<?xml")
+            && markup[5..].starts_with(|ch: char| ch.is_ascii_whitespace())
+        {
+            (TokenKind::Declaration, Self::until(rest, "?>")?)
+        } else if rest.starts_with("<?") {
+            (TokenKind::ProcessingInstruction, Self::until(rest, "?>")?)
+        } else if rest.starts_with("<!--") {
+            (TokenKind::Comment, Self::until(rest, "-->")?)
+        } else if rest.starts_with("<![CDATA[") {
+            (TokenKind::CData, Self::until(rest, "]]>")?)
+        } else if rest.starts_with("<!DOCTYPE") {
+            (TokenKind::Doctype, Self::doctype(rest)?)
+        } else if rest.starts_with("</") {
+            (TokenKind::EndTag, Self::until(rest, ">")?)
+        } else if rest.starts_with('<') {
+            (TokenKind::StartTag, Self::start_tag(rest)?)
+        } else {
+            (TokenKind::Text, rest.find('<').unwrap_or(rest.len()))
+        };
+
+        self.position += length;
+        Some((kind, &rest[..length]))
+    }
+
+    /// Returns the source text which is not yet split
+    pub(crate) fn rest(&self) -> &'a str {
+        &self.text[self.position..]
+    }
+
+    /// Returns the whole source text
+    pub(crate) fn text(&self) -> &'a str {
+        self.text
+    }
+
+    /// Returns the offset right after the last token
+    pub(crate) fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Returns the length of `text` up to the first `end` included
+    fn until(text: &str, end: &str) -> Option<usize> {
+        text.find(end).map(|index| index + end.len())
+    }
+
+    fn start_tag(text: &str) -> Option<usize> {
+        let mut quote = None;
+
+        for (index, ch) in text.char_indices() {
+            match (quote, ch) {
+                (None, '"' | '\'') => quote = Some(ch),
+                (Some(open), _) if open == ch => quote = None,
+                (None, '>') => return Some(index + 1),
+                _ => {}
+            }
+        }
+
+        None
+    }
+
+    /// Returns the length of a `DOCTYPE` declaration, which may hold an
+    /// internal subset enclosed in brackets.
+    fn doctype(text: &str) -> Option<usize> {
+        let mut quote = None;
+        let mut depth = 0;
+
+        for (index, ch) in text.char_indices() {
+            match (quote, ch) {
+                (None, '"' | '\'') => quote = Some(ch),
+                (Some(open), _) if open == ch => quote = None,
+                (None, '[') => depth += 1,
+                (None, ']') => depth -= 1,
+                (None, '>') if depth == 0 => return Some(index + 1),
+                _ => {}
+            }
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_source_into_markup() {
+        let mut lexer = Lexer::new(
+            "<?xml version='1.0'?>\n<!DOCTYPE svg [<!ENTITY a '>'>]><svg a='>'>&a;<![CDATA[<]]><!--c--><?pi?></svg>",
+        );
+        let mut kinds = Vec::new();
+
+        while let Some((kind, _)) = lexer.next_token() {
+            kinds.push(kind);
+        }
+
+        assert_eq!(
+            kinds,
+            vec![
+                TokenKind::Declaration,
+                TokenKind::Text,
+                TokenKind::Doctype,
+                TokenKind::StartTag,
+                TokenKind::Text,
+                TokenKind::CData,
+                TokenKind::Comment,
+                TokenKind::ProcessingInstruction,
+                TokenKind::EndTag,
+            ]
+        );
+    }
+}
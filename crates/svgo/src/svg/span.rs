@@ -0,0 +1,192 @@
+//! Locations of nodes in the source of a SVG document
+
+use std::fmt::Display;
+
+use super::node::Node;
+
+/// Position in the source of a SVG document
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Position {
+    /// Line number, starting at 1
+    pub line: u64,
+    /// Column number in characters, starting at 1
+    pub column: u64,
+}
+
+impl Display for Position {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
+/// Range in the source of a SVG document, `end` being exclusive
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Span {
+    pub start: Position,
+    pub end: Position,
+}
+
+impl Display for Span {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}-{}", self.start, self.end)
+    }
+}
+
+/// Nodes without children along with their location, in the order they
+/// were read.
+pub type NodeSpans = Vec<(Node, Span)>;
+
+/// Locations of an element, its attributes and its children which are not
+/// elements in the source of a SVG document.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ElementSpans {
+    /// From the start tag up to the end tag included
+    pub element: Span,
+    pub start_tag: Span,
+    /// Attributes by their qualified name, e.g. `xlink:href`
+    pub attributes: Vec<(String, Span)>,
+    /// Children which are not elements, as elements hold their own spans
+    pub children: NodeSpans,
+    /// Top-level nodes which are not elements, only kept on the root
+    /// element.
+    pub siblings: NodeSpans,
+}
+
+/// Returns the location of `nodes[index]`, looked up in `spans` unless it is
+/// an element.
+///
+/// Equal nodes are matched in order, so the location is found as long as the
+/// nodes are not reordered.
+pub(crate) fn node_span(nodes: &[Node], index: usize, spans: Option<&NodeSpans>) -> Option<Span> {
+    let node = nodes.get(index)?;
+
+    if let Node::Element(element) = node {
+        return element.span();
+    }
+
+    let nth = nodes[..index].iter().filter(|other| *other == node).count();
+    spans?
+        .iter()
+        .filter(|(other, _)| other == node)
+        .nth(nth)
+        .map(|(_, span)| *span)
+}
+
+/// Maps byte offsets in a source text to line and column positions
+pub(crate) struct SourceMap<'a> {
+    text: &'a str,
+    /// Byte offset where each line starts
+    line_starts: Vec<usize>,
+}
+
+impl<'a> SourceMap<'a> {
+    pub(crate) fn new(text: &'a str) -> Self {
+        let line_starts = std::iter::once(0)
+            .chain(text.match_indices('\n').map(|(index, _)| index + 1))
+            .collect();
+
+        Self { text, line_starts }
+    }
+
+    /// Returns the byte offset of a zero based line and column
+    pub(crate) fn offset(&self, row: u64, column: u64) -> usize {
+        let Some(&start) = self.line_starts.get(row as usize) else {
+            return self.text.len();
+        };
+
+        self.text[start..]
+            .char_indices()
+            .nth(column as usize)
+            .map_or(self.text.len(), |(index, _)| start + index)
+    }
+
+    pub(crate) fn position(&self, offset: usize) -> Position {
+        let row = self.line_starts.partition_point(|&start| start <= offset) - 1;
+        let start = self.line_starts[row];
+        let column = self.text[start..offset.min(self.text.len())]
+            .chars()
+            .count();
+
+        Position {
+            line: row as u64 + 1,
+            column: column as u64 + 1,
+        }
+    }
+
+    pub(crate) fn span(&self, start: usize, end: usize) -> Span {
+        Span {
+            start: self.position(start),
+            end: self.position(end),
+        }
+    }
+
+    /// Locates the start tag beginning at `offset` along with its
+    /// attributes, returns `None` if no start tag is found there.
+    pub(crate) fn start_tag(&self, offset: usize) -> Option<ElementSpans> {
+        let bytes = self.text.as_bytes();
+        // The reader may report the position of the root element past its
+        // opening bracket.
+        let offset = match bytes.get(offset) {
+            Some(b'<') => offset,
+            _ => self.text[..offset.min(bytes.len())].rfind('<')?,
+        };
+        let mut index = skip_while(bytes, offset + 1, |byte| {
+            !byte.is_ascii_whitespace() && byte != b'/' && byte != b'>'
+        });
+
+        let mut attributes = Vec::new();
+
+        loop {
+            index = skip_while(bytes, index, |byte| byte.is_ascii_whitespace());
+
+            match bytes.get(index)? {
+                b'>' => break,
+                b'/' if bytes.get(index + 1) == Some(&b'>') => {
+                    index += 1;
+                    break;
+                }
+                _ => {}
+            }
+
+            let name_start = index;
+            index = skip_while(bytes, index, |byte| {
+                !byte.is_ascii_whitespace() && byte != b'='
+            });
+            let name = &self.text[name_start..index];
+            index = skip_while(bytes, index, |byte| byte.is_ascii_whitespace());
+
+            if bytes.get(index) != Some(&b'=') {
+                return None;
+            }
+
+            index = skip_while(bytes, index + 1, |byte| byte.is_ascii_whitespace());
+            let quote = *bytes.get(index)?;
+            index = skip_while(bytes, index + 1, |byte| byte != quote) + 1;
+
+            attributes.push((name.to_string(), self.span(name_start, index)));
+        }
+
+        let start_tag = self.span(offset, index + 1);
+
+        Some(ElementSpans {
+            element: start_tag,
+            start_tag,
+            attributes,
+            ..Default::default()
+        })
+    }
+
+    /// Returns the offset right after the end of the tag at `offset`
+    pub(crate) fn end_tag(&self, offset: usize) -> usize {
+        let bytes = self.text.as_bytes();
+        (skip_while(bytes, offset, |byte| byte != b'>') + 1).min(bytes.len())
+    }
+}
+
+fn skip_while<F: Fn(u8) -> bool>(bytes: &[u8], mut index: usize, predicate: F) -> usize {
+    while bytes.get(index).is_some_and(|&byte| predicate(byte)) {
+        index += 1;
+    }
+
+    index
+}
@@ -0,0 +1,375 @@
+//! Transform lists for the `transform`, `gradientTransform` and
+//! `patternTransform` attributes
+//! Read more: https://www.w3.org/TR/SVG11/coords.html#TransformAttribute
+
+use std::fmt::Display;
+use std::str::FromStr;
+
+use super::number::{format_number, split_number};
+
+/// 2D affine transformation matrix in the form:
+///
+/// ```text
+/// | a c e |
+/// | b d f |
+/// | 0 0 1 |
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Matrix {
+    pub a: f64,
+    pub b: f64,
+    pub c: f64,
+    pub d: f64,
+    pub e: f64,
+    pub f: f64,
+}
+
+impl Default for Matrix {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+impl Matrix {
+    pub const IDENTITY: Matrix = Matrix::new(1.0, 0.0, 0.0, 1.0, 0.0, 0.0);
+
+    pub const fn new(a: f64, b: f64, c: f64, d: f64, e: f64, f: f64) -> Self {
+        Self { a, b, c, d, e, f }
+    }
+
+    pub fn translate(tx: f64, ty: f64) -> Self {
+        Self::new(1.0, 0.0, 0.0, 1.0, tx, ty)
+    }
+
+    pub fn scale(sx: f64, sy: f64) -> Self {
+        Self::new(sx, 0.0, 0.0, sy, 0.0, 0.0)
+    }
+
+    /// Rotation by `angle` degrees about the origin
+    pub fn rotate(angle: f64) -> Self {
+        let (sin, cos) = angle.to_radians().sin_cos();
+        Self::new(cos, sin, -sin, cos, 0.0, 0.0)
+    }
+
+    pub fn skew_x(angle: f64) -> Self {
+        Self::new(1.0, 0.0, angle.to_radians().tan(), 1.0, 0.0, 0.0)
+    }
+
+    pub fn skew_y(angle: f64) -> Self {
+        Self::new(1.0, angle.to_radians().tan(), 0.0, 1.0, 0.0, 0.0)
+    }
+
+    /// Returns the product of `self` and `other`, which is the
+    /// transformation applying `other` first and `self` second.
+    pub fn multiply(&self, other: &Matrix) -> Matrix {
+        Matrix {
+            a: self.a * other.a + self.c * other.b,
+            b: self.b * other.a + self.d * other.b,
+            c: self.a * other.c + self.c * other.d,
+            d: self.b * other.c + self.d * other.d,
+            e: self.a * other.e + self.c * other.f + self.e,
+            f: self.b * other.e + self.d * other.f + self.f,
+        }
+    }
+
+    /// Transforms the point at `x` and `y`
+    pub fn apply(&self, x: f64, y: f64) -> (f64, f64) {
+        (
+            self.a * x + self.c * y + self.e,
+            self.b * x + self.d * y + self.f,
+        )
+    }
+
+    pub fn determinant(&self) -> f64 {
+        self.a * self.d - self.b * self.c
+    }
+
+    pub fn is_identity(&self) -> bool {
+        *self == Self::IDENTITY
+    }
+
+    /// Returns the components of the matrix in the order they are written
+    pub fn to_array(&self) -> [f64; 6] {
+        [self.a, self.b, self.c, self.d, self.e, self.f]
+    }
+}
+
+/// A single function in a transform list
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Transform {
+    Matrix(Matrix),
+    Translate {
+        tx: f64,
+        ty: f64,
+    },
+    Scale {
+        sx: f64,
+        sy: f64,
+    },
+    /// Rotation by `angle` degrees about the point at `cx` and `cy`
+    Rotate {
+        angle: f64,
+        cx: f64,
+        cy: f64,
+    },
+    SkewX(f64),
+    SkewY(f64),
+}
+
+impl Transform {
+    pub fn to_matrix(&self) -> Matrix {
+        match *self {
+            Self::Matrix(matrix) => matrix,
+            Self::Translate { tx, ty } => Matrix::translate(tx, ty),
+            Self::Scale { sx, sy } => Matrix::scale(sx, sy),
+            Self::Rotate { angle, cx, cy } => Matrix::translate(cx, cy)
+                .multiply(&Matrix::rotate(angle))
+                .multiply(&Matrix::translate(-cx, -cy)),
+            Self::SkewX(angle) => Matrix::skew_x(angle),
+            Self::SkewY(angle) => Matrix::skew_y(angle),
+        }
+    }
+
+    /// Writes this transform in its shortest form, rounding numbers to
+    /// `precision` decimal places when provided.
+    pub fn to_string_with_precision(&self, precision: Option<u8>) -> String {
+        let number = |value: f64| format_number(value, precision);
+        let list = |values: &[f64]| {
+            values
+                .iter()
+                .map(|value| number(*value))
+                .collect::<Vec<String>>()
+                .join(" ")
+        };
+
+        match *self {
+            Self::Matrix(matrix) => format!("matrix({})", list(&matrix.to_array())),
+            Self::Translate { tx, ty } if number(ty) == "0" => {
+                format!("translate({})", number(tx))
+            }
+            Self::Translate { tx, ty } => format!("translate({})", list(&[tx, ty])),
+            Self::Scale { sx, sy } if number(sx) == number(sy) => {
+                format!("scale({})", number(sx))
+            }
+            Self::Scale { sx, sy } => format!("scale({})", list(&[sx, sy])),
+            Self::Rotate { angle, cx, cy } if number(cx) == "0" && number(cy) == "0" => {
+                format!("rotate({})", number(angle))
+            }
+            Self::Rotate { angle, cx, cy } => format!("rotate({})", list(&[angle, cx, cy])),
+            Self::SkewX(angle) => format!("skewX({})", number(angle)),
+            Self::SkewY(angle) => format!("skewY({})", number(angle)),
+        }
+    }
+}
+
+impl Display for Transform {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.to_string_with_precision(None))
+    }
+}
+
+/// Reason for a transform list to be invalid
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TransformErrorKind {
+    /// A transform function other than `matrix`, `translate`, `scale`,
+    /// `rotate`, `skewX` or `skewY` was found
+    UnknownFunction,
+    /// A transform function has the wrong number of arguments
+    InvalidArguments,
+    /// A character which is not valid at the given position was found
+    UnexpectedCharacter(char),
+    /// The transform list ended in the middle of a function
+    UnexpectedEnd,
+}
+
+impl Display for TransformErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnknownFunction => write!(f, "unknown transform function"),
+            Self::InvalidArguments => write!(f, "invalid number of arguments"),
+            Self::UnexpectedCharacter(ch) => write!(f, "unexpected character '{}'", ch),
+            Self::UnexpectedEnd => write!(f, "unexpected end of transform list"),
+        }
+    }
+}
+
+/// Error found when parsing a transform list
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TransformError {
+    /// Byte offset in the transform list where the error was found
+    pub position: usize,
+    pub kind: TransformErrorKind,
+}
+
+impl Display for TransformError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} at position {}", self.kind, self.position)
+    }
+}
+
+impl std::error::Error for TransformError {}
+
+/// Parsed contents of a `transform` attribute
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TransformList(pub Vec<Transform>);
+
+impl TransformList {
+    /// Parses a transform list from an attribute value
+    pub fn parse(value: &str) -> Result<Self, TransformError> {
+        let mut transforms = Vec::new();
+        let mut position = 0;
+        let error = |position, kind| TransformError { position, kind };
+        let skip_separators = |position: usize| {
+            value[position..]
+                .find(|ch: char| ch != ',' && !ch.is_ascii_whitespace())
+                .map_or(value.len(), |offset| position + offset)
+        };
+
+        loop {
+            position = skip_separators(position);
+
+            if position == value.len() {
+                break;
+            }
+
+            let name_end = value[position..]
+                .find(|ch: char| !ch.is_ascii_alphabetic())
+                .map_or(value.len(), |offset| position + offset);
+            let name = &value[position..name_end];
+
+            if name.is_empty() {
+                let ch = value[position..].chars().next().unwrap_or_default();
+                return Err(error(position, TransformErrorKind::UnexpectedCharacter(ch)));
+            }
+
+            let open = name_end
+                + value[name_end..]
+                    .find(|ch: char| !ch.is_ascii_whitespace())
+                    .unwrap_or(value.len() - name_end);
+
+            match value[open..].chars().next() {
+                Some('(') => {}
+                Some(ch) => return Err(error(open, TransformErrorKind::UnexpectedCharacter(ch))),
+                None => return Err(error(open, TransformErrorKind::UnexpectedEnd)),
+            }
+
+            let Some(close) = value[open..].find(')').map(|offset| open + offset) else {
+                return Err(error(value.len(), TransformErrorKind::UnexpectedEnd));
+            };
+
+            let args = value[open + 1..close]
+                .split(|ch: char| ch == ',' || ch.is_ascii_whitespace())
+                .filter(|arg| !arg.is_empty())
+                .map(|arg| match split_number(arg) {
+                    Some((number, "")) => Some(number),
+                    _ => None,
+                })
+                .collect::<Option<Vec<f64>>>()
+                .ok_or(error(open + 1, TransformErrorKind::InvalidArguments))?;
+
+            let transform = match (name, args.as_slice()) {
+                ("matrix", &[a, b, c, d, e, f]) => Transform::Matrix(Matrix::new(a, b, c, d, e, f)),
+                ("translate", &[tx]) => Transform::Translate { tx, ty: 0.0 },
+                ("translate", &[tx, ty]) => Transform::Translate { tx, ty },
+                ("scale", &[sx]) => Transform::Scale { sx, sy: sx },
+                ("scale", &[sx, sy]) => Transform::Scale { sx, sy },
+                ("rotate", &[angle]) => Transform::Rotate {
+                    angle,
+                    cx: 0.0,
+                    cy: 0.0,
+                },
+                ("rotate", &[angle, cx, cy]) => Transform::Rotate { angle, cx, cy },
+                ("skewX", &[angle]) => Transform::SkewX(angle),
+                ("skewY", &[angle]) => Transform::SkewY(angle),
+                ("matrix" | "translate" | "scale" | "rotate" | "skewX" | "skewY", _) => {
+                    return Err(error(open + 1, TransformErrorKind::InvalidArguments));
+                }
+                _ => return Err(error(position, TransformErrorKind::UnknownFunction)),
+            };
+
+            transforms.push(transform);
+            position = close + 1;
+        }
+
+        Ok(Self(transforms))
+    }
+
+    /// Returns the matrix resulting from applying every transform in the
+    /// list, from right to left.
+    pub fn to_matrix(&self) -> Matrix {
+        self.0.iter().fold(Matrix::IDENTITY, |matrix, transform| {
+            matrix.multiply(&transform.to_matrix())
+        })
+    }
+}
+
+impl FromStr for TransformList {
+    type Err = TransformError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl Display for TransformList {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (index, transform) in self.0.iter().enumerate() {
+            if index > 0 {
+                write!(f, " ")?;
+            }
+
+            write!(f, "{}", transform)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_transform_lists() {
+        let list =
+            TransformList::parse("translate(10) scale(2, 3),rotate(90 5 5)skewX(45)").unwrap();
+
+        assert_eq!(
+            list.0,
+            vec![
+                Transform::Translate { tx: 10.0, ty: 0.0 },
+                Transform::Scale { sx: 2.0, sy: 3.0 },
+                Transform::Rotate {
+                    angle: 90.0,
+                    cx: 5.0,
+                    cy: 5.0
+                },
+                Transform::SkewX(45.0),
+            ]
+        );
+        assert_eq!(
+            TransformList::parse("translate(10) rotate(1 2)"),
+            Err(TransformError {
+                position: 21,
+                kind: TransformErrorKind::InvalidArguments
+            })
+        );
+        assert_eq!(
+            TransformList::parse("translate(10) spin(2)")
+                .unwrap_err()
+                .kind,
+            TransformErrorKind::UnknownFunction
+        );
+    }
+
+    #[test]
+    fn composes_transforms_into_matrix() {
+        let list = TransformList::parse("translate(10 20) scale(2)").unwrap();
+
+        assert_eq!(
+            list.to_matrix(),
+            Matrix::new(2.0, 0.0, 0.0, 2.0, 10.0, 20.0)
+        );
+        assert_eq!(list.to_matrix().apply(1.0, 1.0), (12.0, 22.0));
+    }
+}
@@ -1,105 +1,459 @@
-use std::{borrow::Cow, io::Write};
+use std::io::Write;
+use std::str::FromStr;
 
-use anyhow::{Context, Result};
-use xml::{common::XmlVersion, name::Name, namespace::Namespace, writer::XmlEvent, EventWriter};
+use crate::error::{Result, SvgoError};
 
-use super::node::{Attribute, ElementType, Node};
+use super::node::{Attribute, Element, Node};
+use super::parser::is_utf8;
+use super::raw::RawNodes;
 
-pub struct Writer;
+/// Elements whose whitespace is part of their text content
+const TEXT_ELEMENTS: [&str; 7] = [
+    "text", "tspan", "textPath", "title", "desc", "style", "script",
+];
 
-impl Writer {
-    pub fn write<W: Write>(out: W, nodes: &Vec<Node>) -> Result<()> {
-        let mut writer = EventWriter::new(out);
+/// Arrangement of the nodes in a written document
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Layout {
+    /// Writes whitespace as it is in the document
+    #[default]
+    Preserve,
+    /// Writes every node on its own line, indented by its depth
+    Pretty,
+    /// Drops whitespace between tags
+    Minify,
+}
+
+/// Line ending of the lines broken by the writer
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Eol {
+    #[default]
+    Lf,
+    CrLf,
+}
+
+impl Eol {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Lf => "\n",
+            Self::CrLf => "\r\n",
+        }
+    }
+}
+
+impl FromStr for Eol {
+    type Err = SvgoError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "lf" => Ok(Self::Lf),
+            "crlf" => Ok(Self::CrLf),
+            _ => Err(SvgoError::Unsupported(format!("line ending \"{}\"", s))),
+        }
+    }
+}
+
+/// Quote enclosing attribute values
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Quote {
+    #[default]
+    Double,
+    Single,
+}
+
+/// Options used to write a SVG document
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WriterOptions {
+    layout: Layout,
+    indent_size: usize,
+    indent_char: char,
+    eol: Eol,
+    final_newline: bool,
+    attribute_per_line: bool,
+    quote: Quote,
+    self_closing: bool,
+}
+
+impl Default for WriterOptions {
+    fn default() -> Self {
+        Self {
+            layout: Layout::default(),
+            indent_size: 4,
+            indent_char: ' ',
+            eol: Eol::default(),
+            final_newline: false,
+            attribute_per_line: false,
+            quote: Quote::default(),
+            self_closing: true,
+        }
+    }
+}
+
+impl WriterOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets how nodes are arranged, refer to [`Layout`].
+    ///
+    /// Source text recorded in lossless mode is only used with
+    /// [`Layout::Preserve`].
+    pub fn layout(mut self, layout: Layout) -> Self {
+        self.layout = layout;
+        self
+    }
+
+    /// Sets the indentation of each depth level with [`Layout::Pretty`]
+    pub fn indent(mut self, size: usize, ch: char) -> Self {
+        self.indent_size = size;
+        self.indent_char = ch;
+        self
+    }
+
+    /// Sets the line ending of the lines broken by the writer, line endings
+    /// in text are kept as they are.
+    pub fn eol(mut self, eol: Eol) -> Self {
+        self.eol = eol;
+        self
+    }
+
+    /// Enables or disables ending the document with a line ending
+    pub fn final_newline(mut self, final_newline: bool) -> Self {
+        self.final_newline = final_newline;
+        self
+    }
+
+    /// Enables or disables writing each attribute of elements with several
+    /// attributes on its own line with [`Layout::Pretty`].
+    pub fn attribute_per_line(mut self, attribute_per_line: bool) -> Self {
+        self.attribute_per_line = attribute_per_line;
+        self
+    }
+
+    pub fn quote(mut self, quote: Quote) -> Self {
+        self.quote = quote;
+        self
+    }
+
+    /// Enables or disables writing elements without children as a single
+    /// self-closing tag such as `<path/>`, rather than `<path></path>`.
+    pub fn self_closing(mut self, self_closing: bool) -> Self {
+        self.self_closing = self_closing;
+        self
+    }
+}
+
+pub struct Writer<'a> {
+    options: &'a WriterOptions,
+    text: String,
+}
+
+impl Writer<'_> {
+    /// Writes `nodes` as a XML document.
+    ///
+    /// Nodes read with [`Parser::lossless`] which are left untouched are
+    /// written as they were read, other nodes are serialized.
+    ///
+    /// [`Parser::lossless`]: super::parser::Parser::lossless
+    pub fn write<W: Write>(out: W, nodes: &[Node]) -> Result<()> {
+        Self::write_with(out, nodes, &WriterOptions::default())
+    }
+
+    /// Writes `nodes` as a XML document with the provided options
+    pub fn write_with<W: Write>(mut out: W, nodes: &[Node], options: &WriterOptions) -> Result<()> {
+        let siblings = nodes
+            .iter()
+            .find_map(|node| node.as_element()?.raw.as_ref())
+            .map(|raw| &raw.siblings);
+        let mut writer = Writer {
+            options,
+            text: String::new(),
+        };
+
+        writer.write_nodes(nodes, siblings, 0, false);
+
+        if options.final_newline && !writer.text.ends_with('\n') {
+            writer.text.push_str(options.eol.as_str());
+        }
+
+        out.write_all(writer.text.as_bytes())
+            .map_err(|err| SvgoError::Write(Box::new(err)))
+    }
+
+    /// Writes nodes at `depth`, whitespace between them is rearranged
+    /// according to the layout unless it is `significant`.
+    fn write_nodes(
+        &mut self,
+        nodes: &[Node],
+        raw: Option<&RawNodes>,
+        depth: usize,
+        significant: bool,
+    ) {
+        let rearranged = self.options.layout != Layout::Preserve && !significant;
+        let mut raw = match self.options.layout {
+            Layout::Preserve => raw.map_or(&[][..], Vec::as_slice),
+            _ => &[],
+        };
+        let mut first = true;
 
         for node in nodes {
-            match node {
-                Node::Declaration { version, .. } => {
-                    let version = match version {
-                        super::node::Version::Version10 => XmlVersion::Version10,
-                        super::node::Version::Version11 => XmlVersion::Version11,
-                    };
-
-                    writer
-                        .write(XmlEvent::StartDocument {
-                            version,
-                            encoding: None,
-                            standalone: None,
-                        })
-                        .context("Failed to write SVG Start Document")?;
+            if rearranged && is_blank(node) {
+                continue;
+            }
+
+            if rearranged && self.options.layout == Layout::Pretty && (depth > 0 || !first) {
+                self.break_line(depth);
+            }
+
+            first = false;
+
+            if let Node::Element(element) = node {
+                self.write_element(element, depth, significant);
+                continue;
+            }
+
+            // Nodes are kept in order, so the source text of a node follows
+            // the one of the previous node.
+            match raw.iter().position(|(original, _)| original == node) {
+                Some(index) => {
+                    self.text.push_str(&raw[index].1);
+                    raw = &raw[index + 1..];
                 }
-                Node::Doctype(value) => {
-                    writer
-                        .inner_mut()
-                        .write(value.as_bytes())
-                        .context("Failed to write SVG characters")?;
+                None => self.write_node(node),
+            }
+        }
+    }
+
+    fn write_element(&mut self, element: &Element, depth: usize, significant: bool) {
+        let raw = match self.options.layout {
+            Layout::Preserve => element.raw.as_deref(),
+            _ => None,
+        };
+        let name = element.qualified_name();
+        let significant = significant
+            || TEXT_ELEMENTS.contains(&name.as_str())
+            || element.attribute("xml:space") == Some("preserve")
+            || element.children.iter().any(|child| match child {
+                Node::Characters(value) => !value.trim().is_empty(),
+                Node::CData(_) => true,
+                _ => false,
+            });
+        let rearranged = self.options.layout != Layout::Preserve && !significant;
+        let is_empty = match rearranged {
+            true => element.children.iter().all(is_blank),
+            false => element.children.is_empty(),
+        };
+
+        match raw {
+            // A self-closing start tag cannot hold children added since
+            Some(raw) if raw.matches(element) && (is_empty || !raw.is_self_closing()) => {
+                self.text.push_str(&raw.start_tag);
+
+                if raw.is_self_closing() {
+                    return;
                 }
-                Node::Element(element) => {
-                    let name = element.name.as_str();
-                    let name = Name::local(name);
-                    let namespace = Cow::Owned(Namespace::empty());
-                    let attributes = element
-                        .attributes
-                        .iter()
-                        .map(|attr| match attr {
-                            Attribute::Local { key, value } => xml::attribute::Attribute {
-                                name: Name::local(key.as_str()),
-                                value: value.as_str(),
-                            },
-                            Attribute::Namespaced {
-                                key,
-                                value,
-                                namespace,
-                                prefix,
-                            } => xml::attribute::Attribute {
-                                name: Name::qualified(
-                                    key,
-                                    namespace,
-                                    prefix.as_ref().map(|p| p.as_str()),
-                                ),
-                                value: value.as_str(),
-                            },
-                            Attribute::Declaration { key, value } => xml::attribute::Attribute {
-                                name: Name::local(key.as_str()),
-                                value: value.as_str(),
-                            },
-                        })
-                        .collect();
-
-                    match element.r#type {
-                        ElementType::Open => {
-                            writer
-                                .write(XmlEvent::StartElement {
-                                    name,
-                                    attributes,
-                                    namespace,
-                                })
-                                .context("Failed to write SVG Start Element")?;
-                        }
-                        ElementType::Close => {
-                            writer
-                                .write(XmlEvent::EndElement { name: None })
-                                .context("Failed to write SVG End Element")?;
-                        }
+            }
+            _ => {
+                self.text.push('<');
+                self.text.push_str(&name);
+
+                let attribute_per_line = self.options.attribute_per_line
+                    && self.options.layout == Layout::Pretty
+                    && element.attributes.len() > 1;
+
+                for attr in &element.attributes {
+                    match attribute_per_line {
+                        true => self.break_line(depth + 1),
+                        false => self.text.push(' '),
                     }
+
+                    self.write_attribute(attr);
                 }
-                Node::Comment(value) => {
-                    writer
-                        .write(XmlEvent::Comment(value))
-                        .context("Failed to write SVG comment")?;
+
+                if is_empty {
+                    match self.options.self_closing {
+                        true => self.text.push_str("/>"),
+                        false => self.text.push_str(&format!("></{}>", name)),
+                    }
+
+                    return;
                 }
-                Node::CData(value) => {
-                    writer
-                        .write(XmlEvent::CData(value))
-                        .context("Failed to write SVG CDATA")?;
+
+                self.text.push('>');
+            }
+        }
+
+        self.write_nodes(
+            &element.children,
+            raw.map(|raw| &raw.children),
+            depth + 1,
+            significant,
+        );
+
+        if rearranged && self.options.layout == Layout::Pretty {
+            self.break_line(depth);
+        }
+
+        match raw.and_then(|raw| Some((raw.original.qualified_name(), raw.end_tag.as_ref()?))) {
+            Some((original, end_tag)) if original == name => self.text.push_str(end_tag),
+            _ => self.text.push_str(&format!("</{}>", name)),
+        }
+    }
+
+    fn write_attribute(&mut self, attr: &Attribute) {
+        let quote = match self.options.quote {
+            Quote::Double => '"',
+            Quote::Single => '\'',
+        };
+
+        if let Attribute::Namespaced {
+            prefix: Some(prefix),
+            ..
+        } = attr
+        {
+            self.text.push_str(prefix);
+            self.text.push(':');
+        }
+
+        self.text.push_str(&format!(
+            "{}={}{}{}",
+            attr.key(),
+            quote,
+            escape(attr.value(), true),
+            quote
+        ));
+    }
+
+    fn write_node(&mut self, node: &Node) {
+        let text = &mut self.text;
+
+        match node {
+            Node::Declaration {
+                version,
+                encoding,
+                standalone,
+            } => {
+                text.push_str(&format!("<?xml version=\"{}\"", version));
+
+                // Documents are always written with UTF-8
+                if let Some(encoding) = encoding {
+                    let encoding = if is_utf8(encoding) { encoding } else { "UTF-8" };
+                    text.push_str(&format!(" encoding=\"{}\"", encoding));
                 }
-                Node::Characters(value) => {
-                    writer
-                        .write(XmlEvent::Characters(value))
-                        .context("Failed to write SVG characters")?;
+
+                if let Some(standalone) = standalone {
+                    let standalone = if *standalone { "yes" } else { "no" };
+                    text.push_str(&format!(" standalone=\"{}\"", standalone));
                 }
+
+                text.push_str("?>");
             }
+            Node::Doctype(value) => text.push_str(value),
+            Node::ProcessingInstruction { name, data } => match data {
+                Some(data) => text.push_str(&format!("<?{} {}?>", name, data)),
+                None => text.push_str(&format!("<?{}?>", name)),
+            },
+            Node::Element(element) => self.write_element(element, 0, false),
+            Node::Comment(value) => {
+                // Comments are padded with a space unless they already are
+                let start = if value.starts_with(char::is_whitespace) {
+                    ""
+                } else {
+                    " "
+                };
+                let end = if value.ends_with(char::is_whitespace) {
+                    ""
+                } else {
+                    " "
+                };
+                text.push_str(&format!("<!--{}{}{}-->", start, value, end));
+            }
+            Node::CData(value) => text.push_str(&format!("<![CDATA[{}]]>", value)),
+            Node::Characters(value) => text.push_str(&escape(value, false)),
+        }
+    }
+
+    /// Starts a new line indented to `depth`
+    fn break_line(&mut self, depth: usize) {
+        self.text.push_str(self.options.eol.as_str());
+
+        for _ in 0..depth * self.options.indent_size {
+            self.text.push(self.options.indent_char);
+        }
+    }
+}
+
+/// Returns `true` if the node only holds whitespace
+fn is_blank(node: &Node) -> bool {
+    matches!(node, Node::Characters(value) if value.trim().is_empty())
+}
+
+/// Escapes markup characters in text, or in an attribute value if
+/// `attribute` is set.
+fn escape(value: &str, attribute: bool) -> String {
+    let mut escaped = String::with_capacity(value.len());
+
+    for ch in value.chars() {
+        match ch {
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '&' => escaped.push_str("&amp;"),
+            '"' if attribute => escaped.push_str("&quot;"),
+            '\'' if attribute => escaped.push_str("&apos;"),
+            '\n' if attribute => escaped.push_str("&#xA;"),
+            '\r' if attribute => escaped.push_str("&#xD;"),
+            ch => escaped.push(ch),
         }
+    }
+
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::svg::parser::Parser;
+
+    const SOURCE: &str = "<svg a=\"1\" b=\"2\">\n  <g>\n <rect/>\n  </g>\n  <text> a <tspan>b</tspan></text>\n</svg>";
+
+    fn write(options: &WriterOptions) -> String {
+        let nodes = Parser::read(SOURCE.as_bytes()).unwrap();
+        let mut output = Vec::new();
+        Writer::write_with(&mut output, &nodes, options).unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn writes_pretty_documents() {
+        let options = WriterOptions::new()
+            .layout(Layout::Pretty)
+            .indent(1, '\t')
+            .attribute_per_line(true)
+            .quote(Quote::Single)
+            .final_newline(true);
+
+        assert_eq!(
+            write(&options),
+            "<svg\n\ta='1'\n\tb='2'>\n\t<g>\n\t\t<rect/>\n\t</g>\n\t<text> a <tspan>b</tspan></text>\n</svg>\n"
+        );
+    }
+
+    #[test]
+    fn keeps_empty_elements_open_when_requested() {
+        let options = WriterOptions::new()
+            .layout(Layout::Minify)
+            .self_closing(false);
+
+        assert!(write(&options).contains("<g><rect></rect></g>"));
+    }
+
+    #[test]
+    fn writes_minified_documents() {
+        let options = WriterOptions::new().layout(Layout::Minify).eol(Eol::CrLf);
 
-        Ok(())
+        assert_eq!(
+            write(&options),
+            "<svg a=\"1\" b=\"2\"><g><rect/></g><text> a <tspan>b</tspan></text></svg>"
+        );
     }
 }
@@ -4,6 +4,10 @@ mod parser;
 #[cfg(test)]
 mod node;
 
+#[cfg(test)]
+mod writer;
+
 pub mod fixtures {
     pub const JAPAN_SVG: &[u8] = include_bytes!("../fixtures/japan.svg");
+    pub const CAR_SVG: &[u8] = include_bytes!("../fixtures/car.svg");
 }
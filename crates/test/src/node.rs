@@ -1,18 +1,231 @@
 use svgo::svg::node::{Attribute, Node};
 use svgo::svg::parser::Parser;
+use svgo::svg::writer::Writer;
 
-use crate::fixtures::JAPAN_SVG;
+use crate::fixtures::{CAR_SVG, JAPAN_SVG};
+
+fn collect_attributes(nodes: Vec<Node>, attributes: &mut Vec<Attribute>) {
+    for node in nodes {
+        if let Node::Element(element) = node {
+            attributes.extend(element.attributes);
+            collect_attributes(element.children, attributes);
+        }
+    }
+}
 
 #[test]
 fn parses_svg_local_attributes() {
     let nodes = Parser::read(JAPAN_SVG).expect("Failed to parse SVG");
-    let attributes: Vec<Attribute> = nodes
-        .into_iter()
-        .filter_map(|node| match node {
-            Node::Element(element) => Some(element.attributes),
-            _ => None,
-        })
-        .flatten()
-        .collect();
+    let mut attributes: Vec<Attribute> = Vec::new();
+    collect_attributes(nodes, &mut attributes);
+    attributes.retain(|attr| !matches!(attr, Attribute::Declaration { .. }));
     assert_eq!(attributes.len(), 15);
 }
+
+#[test]
+fn parses_namespace_declarations_where_they_appear() {
+    let source = r##"<svg xmlns="http://www.w3.org/2000/svg"><g xmlns:xlink="http://www.w3.org/1999/xlink"><use xlink:href="#a"/></g></svg>"##;
+    let nodes = Parser::read(source.as_bytes()).expect("Failed to parse SVG");
+    let mut attributes: Vec<Attribute> = Vec::new();
+    collect_attributes(nodes.clone(), &mut attributes);
+
+    assert_eq!(
+        attributes
+            .iter()
+            .filter(|attr| matches!(attr, Attribute::Declaration { .. }))
+            .map(ToString::to_string)
+            .collect::<Vec<String>>(),
+        vec![
+            r#"xmlns="http://www.w3.org/2000/svg""#,
+            r#"xmlns:xlink="http://www.w3.org/1999/xlink""#,
+        ]
+    );
+
+    let mut output = Vec::new();
+    Writer::write(&mut output, &nodes).expect("Failed to write SVG");
+
+    assert!(String::from_utf8(output).unwrap().ends_with(
+        r##"<svg xmlns="http://www.w3.org/2000/svg"><g xmlns:xlink="http://www.w3.org/1999/xlink"><use xlink:href="#a"/></g></svg>"##
+    ));
+}
+
+#[test]
+fn writes_namespace_declarations_in_source_order() {
+    let source = r##"<svg width="10" xmlns:xlink="http://www.w3.org/1999/xlink" xmlns="http://www.w3.org/2000/svg" xmlns:inkscape="http://www.inkscape.org/namespaces/inkscape" height="10"><g xmlns:xlink="http://www.w3.org/1999/xlink" id="a"><use xlink:href="#a"/></g></svg>"##;
+    let nodes = Parser::read(source.as_bytes()).expect("Failed to parse SVG");
+    let mut output = Vec::new();
+    Writer::write(&mut output, &nodes).expect("Failed to write SVG");
+
+    assert_eq!(String::from_utf8(output).unwrap(), source);
+}
+
+#[test]
+fn parses_svg_into_tree() {
+    let nodes = Parser::read(JAPAN_SVG).expect("Failed to parse SVG");
+    let root = nodes
+        .iter()
+        .find_map(Node::as_element)
+        .expect("Missing root element");
+    let children: Vec<&str> = root
+        .children
+        .iter()
+        .filter_map(Node::as_element)
+        .map(|element| element.name.as_str())
+        .collect();
+
+    assert_eq!(root.name, "svg");
+    assert_eq!(children, vec!["style", "g", "g"]);
+}
+
+#[test]
+fn keeps_prefixed_element_names() {
+    let source = r#"<svg xmlns="http://www.w3.org/2000/svg" xmlns:svg="http://www.w3.org/2000/svg" xmlns:inkscape="http://www.inkscape.org/namespaces/inkscape"><svg:rect width="10"/><inkscape:grid/></svg>"#;
+    let nodes = Parser::read(source.as_bytes()).expect("Failed to parse SVG");
+    let root = nodes
+        .iter()
+        .find_map(Node::as_element)
+        .expect("Missing root element");
+    let children: Vec<(String, Option<&str>)> = root
+        .children
+        .iter()
+        .filter_map(Node::as_element)
+        .map(|element| (element.qualified_name(), element.namespace.as_deref()))
+        .collect();
+
+    assert_eq!(root.prefix, None);
+    assert_eq!(
+        root.namespace.as_deref(),
+        Some("http://www.w3.org/2000/svg")
+    );
+    assert_eq!(
+        children,
+        vec![
+            ("svg:rect".to_string(), Some("http://www.w3.org/2000/svg")),
+            (
+                "inkscape:grid".to_string(),
+                Some("http://www.inkscape.org/namespaces/inkscape")
+            ),
+        ]
+    );
+
+    let mut output = Vec::new();
+    Writer::write(&mut output, &nodes).expect("Failed to write SVG");
+    let output = String::from_utf8(output).unwrap();
+
+    assert!(output.contains(r#"<svg:rect width="10"/>"#));
+    assert!(output.contains("<inkscape:grid/>"));
+}
+
+#[test]
+fn writes_xml_declaration_as_declared() {
+    let documents = vec![
+        r#"<?xml version="1.0" encoding="utf-8" standalone="no"?><svg/>"#,
+        r#"<?xml version="1.1"?><svg/>"#,
+        "<svg/>",
+    ];
+
+    for source in documents {
+        let nodes = Parser::read(source.as_bytes()).expect("Failed to parse SVG");
+        let mut output = Vec::new();
+        Writer::write(&mut output, &nodes).expect("Failed to write SVG");
+
+        assert_eq!(String::from_utf8(output).unwrap(), source);
+    }
+}
+
+#[test]
+fn writes_latin_1_documents_as_utf_8() {
+    let source = b"<?xml version=\"1.0\" encoding=\"ISO-8859-1\"?><svg><text>caf\xE9</text></svg>";
+    let nodes = Parser::read(&source[..]).expect("Failed to parse SVG");
+    let mut output = Vec::new();
+    Writer::write(&mut output, &nodes).expect("Failed to write SVG");
+
+    assert_eq!(
+        String::from_utf8(output.clone()).unwrap(),
+        r#"<?xml version="1.0" encoding="UTF-8"?><svg><text>café</text></svg>"#
+    );
+    assert_eq!(Parser::read(output.as_slice()).unwrap()[1..], nodes[1..]);
+}
+
+#[test]
+fn writes_utf_16_documents_as_utf_8() {
+    let text = r#"<?xml version="1.0" encoding="UTF-16"?><svg><text>café</text></svg>"#;
+    let source: Vec<u8> = std::iter::once(0xFEFF)
+        .chain(text.encode_utf16())
+        .flat_map(u16::to_le_bytes)
+        .collect();
+    let nodes = Parser::read(source.as_slice()).expect("Failed to parse SVG");
+    let mut output = Vec::new();
+    Writer::write(&mut output, &nodes).expect("Failed to write SVG");
+
+    assert_eq!(
+        String::from_utf8(output.clone()).unwrap(),
+        text.replace("UTF-16", "UTF-8")
+    );
+    assert_eq!(Parser::read(output.as_slice()).unwrap()[1..], nodes[1..]);
+}
+
+#[test]
+fn keeps_processing_instructions() {
+    let source = r#"<?xml version="1.0"?><?xml-stylesheet href="a.css" type="text/css"?><svg/>"#;
+    let nodes = Parser::read(source.as_bytes()).expect("Failed to parse SVG");
+
+    assert_eq!(
+        nodes[1],
+        Node::ProcessingInstruction {
+            name: "xml-stylesheet".to_string(),
+            data: Some(r#"href="a.css" type="text/css""#.to_string()),
+        }
+    );
+
+    let mut output = Vec::new();
+    Writer::write(&mut output, &nodes).expect("Failed to write SVG");
+
+    assert_eq!(String::from_utf8(output).unwrap(), source);
+}
+
+#[test]
+fn writes_untouched_documents_as_read_in_lossless_mode() {
+    for source in [JAPAN_SVG, CAR_SVG] {
+        let nodes = Parser::new()
+            .lossless(true)
+            .parse(source)
+            .expect("Failed to parse SVG")
+            .nodes;
+        let mut output = Vec::new();
+        Writer::write(&mut output, &nodes).expect("Failed to write SVG");
+
+        assert_eq!(
+            String::from_utf8_lossy(&output),
+            String::from_utf8_lossy(source)
+        );
+    }
+}
+
+#[test]
+fn writes_changed_nodes_in_lossless_mode() {
+    let source = "<?xml version='1.0'?>\n<svg  width = '10'>\n  <rect x='1'/>\n  <!--a-->\n  <g fill='red'></g>\n</svg>\n";
+    let mut nodes = Parser::new()
+        .lossless(true)
+        .parse(source.as_bytes())
+        .expect("Failed to parse SVG")
+        .nodes;
+    let root = nodes
+        .iter_mut()
+        .find_map(Node::as_element_mut)
+        .expect("Missing root element");
+    root.children
+        .retain(|node| !matches!(node, Node::Comment(_)));
+    root.children
+        .iter_mut()
+        .filter_map(Node::as_element_mut)
+        .for_each(|element| element.set_attribute("x", "2"));
+
+    let mut output = Vec::new();
+    Writer::write(&mut output, &nodes).expect("Failed to write SVG");
+
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        "<?xml version='1.0'?>\n<svg  width = '10'>\n  <rect x=\"2\"/>\n  \n  <g fill=\"red\" x=\"2\"/>\n</svg>\n"
+    );
+}
@@ -1,17 +1,132 @@
-// #[test]
-// fn parses_svg_file() {
-//     let svg = Svg::read(JAPAN_SVG).unwrap();
-//     let nodes = svg.nodes();
-//     assert_eq!(nodes.len(), 30);
-// }
-
-// #[test]
-// fn read_writes_svg() {
-//     let input_str = String::from_utf8(JAPAN_SVG.to_vec()).unwrap();
-//     let svg = Svg::read(JAPAN_SVG).unwrap();
-//     let mut output_bytes: Vec<u8> = Vec::new();
-//     let buf = BufWriter::new(&mut output_bytes);
-//     svg.write(buf).unwrap();
-//     let output_str = String::from_utf8(output_bytes).unwrap();
-//     assert_eq!(input_str, output_str);
-// }
+use svgo::svg::node::{Element, Node, NodeRef};
+use svgo::svg::parser::Parser;
+use svgo::svg::span::{Position, Span};
+use svgo::svg::Svg;
+use svgo::SvgoError;
+
+use crate::fixtures::JAPAN_SVG;
+
+#[test]
+fn read_writes_svg() {
+    let (svg, _) =
+        Svg::read_with(JAPAN_SVG, &Parser::new().lossless(true)).expect("Failed to parse SVG");
+    let mut output = Vec::new();
+    svg.write(&mut output).expect("Failed to write SVG");
+
+    assert_eq!(output, JAPAN_SVG);
+}
+
+const TRUNCATED_SVG: &[u8] =
+    b"<svg>\n  <g>\n    <rect width=\"10\"/>\n    <path d=\"M0 0\" fill=<\n</svg>";
+
+#[test]
+fn reports_malformed_documents() {
+    let err = match Parser::read(TRUNCATED_SVG) {
+        Err(SvgoError::Parse(err)) => err,
+        other => panic!("Expected a parse error, got {:?}", other),
+    };
+
+    assert_eq!(err.line, 4);
+    assert_eq!(err.construct, "<g>");
+    assert!(err.snippet.contains("fill=<"));
+}
+
+#[test]
+fn recovers_nodes_read_before_errors() {
+    let parsed = Parser::new()
+        .recover(true)
+        .parse(TRUNCATED_SVG)
+        .expect("Failed to recover from parse error");
+
+    assert_eq!(parsed.warnings.len(), 1);
+
+    let root = parsed
+        .nodes
+        .iter()
+        .find_map(Node::as_element)
+        .expect("Missing root element");
+    let group = root
+        .children
+        .iter()
+        .find_map(Node::as_element)
+        .expect("Missing group");
+
+    assert_eq!(group.name, "g");
+    assert!(group
+        .children
+        .iter()
+        .filter_map(Node::as_element)
+        .any(|element| element.name == "rect"));
+}
+
+#[test]
+fn records_source_spans_when_enabled() {
+    let source = "<svg>\n  <rect x=\"1\" y='2'/>\n  <g\n    fill=\"red\">\n  </g>\n</svg>";
+    let parsed = Parser::new()
+        .spans(true)
+        .parse(source.as_bytes())
+        .expect("Failed to parse SVG");
+    let root = parsed
+        .nodes
+        .iter()
+        .find_map(Node::as_element)
+        .expect("Missing root element");
+    let children: Vec<&Element> = root.children.iter().filter_map(Node::as_element).collect();
+
+    let span = |line, column, end_line, end_column| Span {
+        start: Position { line, column },
+        end: Position {
+            line: end_line,
+            column: end_column,
+        },
+    };
+
+    assert_eq!(root.span(), Some(span(1, 1, 6, 7)));
+    assert_eq!(children[0].span(), Some(span(2, 3, 2, 22)));
+    assert_eq!(children[0].attribute_span("y"), Some(span(2, 15, 2, 20)));
+    assert_eq!(children[1].span(), Some(span(3, 3, 5, 7)));
+    assert_eq!(children[1].attribute_span("fill"), Some(span(4, 5, 4, 15)));
+
+    let nodes = Parser::read(source.as_bytes()).expect("Failed to parse SVG");
+    assert_eq!(nodes[0].as_element().unwrap().span(), None);
+}
+
+#[test]
+fn records_spans_of_every_node_kind() {
+    let source = "<?xml version=\"1.0\"?>\n<!DOCTYPE svg>\n<?pi a?>\n<svg><!--c--><![CDATA[d]]>\n  e<?f?></svg>\n";
+    let (svg, _) =
+        Svg::read_with(source.as_bytes(), &Parser::new().spans(true)).expect("Failed to parse SVG");
+
+    let span = |line, column, end_line, end_column| Span {
+        start: Position { line, column },
+        end: Position {
+            line: end_line,
+            column: end_column,
+        },
+    };
+    let spans = |nodes: Vec<NodeRef>| nodes.iter().map(NodeRef::span).collect::<Vec<_>>();
+    let root = svg.root().expect("Missing root element");
+
+    assert_eq!(
+        spans((0..8).filter_map(|index| svg.get(&[index])).collect()),
+        vec![
+            Some(span(1, 1, 1, 22)),
+            Some(span(1, 22, 2, 1)),
+            Some(span(2, 1, 2, 15)),
+            Some(span(2, 15, 3, 1)),
+            Some(span(3, 1, 3, 9)),
+            Some(span(3, 9, 4, 1)),
+            Some(span(4, 1, 5, 15)),
+            Some(span(5, 15, 6, 1)),
+        ]
+    );
+    assert_eq!(
+        spans(root.children().collect()),
+        vec![
+            Some(span(4, 6, 4, 14)),
+            Some(span(4, 14, 4, 27)),
+            Some(span(4, 27, 5, 4)),
+            Some(span(5, 4, 5, 9)),
+        ]
+    );
+}
@@ -0,0 +1,49 @@
+use svgo::svg::parser::Parser;
+use svgo::svg::writer::Writer;
+
+/// Markup the serializer escapes and pads, quoted and spaced unlike its output
+const MARKUP_SVG: &[u8] = br#"<?xml version='1.0' encoding='UTF-8'?>
+<?xml-stylesheet href="a.css"?>
+<svg xmlns="http://www.w3.org/2000/svg"  xmlns:xlink = 'http://www.w3.org/1999/xlink'>
+  <!--no padding--><!-- padded -->
+  <g title='it&apos;s "quoted"' data-lines="a&#xA;b&#xD;" >
+    <use xlink:href='#a'></use>
+    <text>a &lt; b &amp;&amp; c &gt; d, it's "fine"</text>
+    <style><![CDATA[rect > g { fill: red; }]]></style>
+  </g>
+</svg>
+"#;
+
+#[test]
+fn serializes_documents_without_lossless_mode() {
+    let nodes = Parser::read(MARKUP_SVG).expect("Failed to parse SVG");
+    let mut output = Vec::new();
+    Writer::write(&mut output, &nodes).expect("Failed to write SVG");
+
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        r##"<?xml version="1.0" encoding="UTF-8"?>
+<?xml-stylesheet href="a.css"?>
+<svg xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink">
+  <!-- no padding --><!-- padded -->
+  <g title="it&apos;s &quot;quoted&quot;" data-lines="a&#xA;b&#xD;">
+    <use xlink:href="#a"/>
+    <text>a &lt; b &amp;&amp; c &gt; d, it's "fine"</text>
+    <style><![CDATA[rect > g { fill: red; }]]></style>
+  </g>
+</svg>"##
+    );
+}
+
+#[test]
+fn writes_source_text_in_lossless_mode() {
+    let nodes = Parser::new()
+        .lossless(true)
+        .parse(MARKUP_SVG)
+        .expect("Failed to parse SVG")
+        .nodes;
+    let mut output = Vec::new();
+    Writer::write(&mut output, &nodes).expect("Failed to write SVG");
+
+    assert_eq!(output, MARKUP_SVG);
+}